@@ -0,0 +1,193 @@
+//! Access control for introspection data
+//!
+//! Tool history and usage statistics belong to the connection that produced
+//! them. An [`AccessPolicy`] decides whether a calling connection may read
+//! data beyond its own:
+//!
+//! - `own_connection` (default): only the calling connection's history and
+//!   statistics
+//! - `same_user`: connections recorded in a stats file owned by the same OS
+//!   user, and those stats files. kodegend does not record which user a
+//!   connection belongs to, so the owner of the stats files of the servers
+//!   that served it stands in; connections not yet in any stats file are denied
+//! - `admin`: everything, including stats files owned by other users
+//!
+//! Reading other connections is opt-in because their history holds
+//! arguments and outputs of every tool they called.
+//!
+//! `kodegen_config_manager`'s `config.json` has a fixed set of keys, so the
+//! policy lives next to it in the kodegen config directory, in
+//! `introspection-access.json`:
+//!
+//! ```json
+//! { "policy": "same_user" }
+//! ```
+//!
+//! Only the user-global config directory is consulted; a repository's local
+//! `.kodegen/` cannot widen access.
+
+use kodegen_mcp_schema::McpError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::usage_tracker::{stats_instance_id, InstanceStats, UsageTracker};
+
+/// Policy file name inside `KodegenConfig::config_dir()`
+pub const ACCESS_CONFIG_FILE: &str = "introspection-access.json";
+
+/// Who may read which introspection data
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessPolicy {
+    /// A connection may only inspect itself
+    #[default]
+    OwnConnection,
+    /// Connections and stats files of the same OS user
+    SameUser,
+    /// Unrestricted
+    Admin,
+}
+
+/// On-disk layout of `introspection-access.json`
+#[derive(Debug, Default, Deserialize)]
+struct AccessConfig {
+    #[serde(default)]
+    policy: AccessPolicy,
+}
+
+impl AccessPolicy {
+    /// Path of the policy file
+    pub fn config_path() -> Option<PathBuf> {
+        kodegen_config::KodegenConfig::config_dir()
+            .ok()
+            .map(|dir| dir.join(ACCESS_CONFIG_FILE))
+    }
+
+    /// Load the configured policy, or the default when there is no policy file
+    ///
+    /// A policy file that cannot be parsed is an error rather than a silent
+    /// fallback, so a typo cannot widen access.
+    pub fn load() -> Result<Self, McpError> {
+        let Some(path) = Self::config_path() else {
+            return Ok(Self::default());
+        };
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(McpError::Other(anyhow::anyhow!("Failed to read access policy {}: {}", path.display(), e)));
+            }
+        };
+        serde_json::from_str::<AccessConfig>(&text)
+            .map(|config| config.policy)
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Invalid access policy {}: {}", path.display(), e)))
+    }
+
+    /// Name used in configuration and error messages
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::OwnConnection => "own_connection",
+            Self::SameUser => "same_user",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Check that `requester` may read the history and statistics of `target`
+    pub fn authorize_connection(self, requester: &str, target: &str) -> Result<(), McpError> {
+        if requester == target {
+            return Ok(());
+        }
+        if self == Self::OwnConnection {
+            return Err(self.denied(&format!("connection '{requester}' may not inspect connection '{target}'")));
+        }
+        self.authorize_other_connection(target)
+    }
+
+    /// Check that a requester without a connection of its own may read `target`
+    pub fn authorize_other_connection(self, target: &str) -> Result<(), McpError> {
+        self.authorize_cross_connection(&format!("history of connection '{target}'"))?;
+        if self == Self::SameUser && !connection_owned_by_current_user(target) {
+            return Err(self.denied(&format!(
+                "connection '{target}' is not recorded in a stats file owned by this user"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check that the requester may read data aggregated across connections
+    pub fn authorize_cross_connection(self, what: &str) -> Result<(), McpError> {
+        if self == Self::OwnConnection {
+            return Err(self.denied(&format!("{what} includes other connections' data")));
+        }
+        Ok(())
+    }
+
+    /// Check that the requester may read one instance's stats file
+    pub fn authorize_instance(self, instance_id: &str) -> Result<(), McpError> {
+        self.authorize_cross_connection(&format!("stats file of instance '{instance_id}'"))?;
+        let path = UsageTracker::get_stats_file_path(instance_id);
+        if self == Self::SameUser && !owned_by_current_user(&path) {
+            return Err(self.denied(&format!("stats file of instance '{instance_id}' belongs to another user")));
+        }
+        Ok(())
+    }
+
+    /// Keep the instances whose stats files the requester may read
+    #[must_use]
+    pub fn visible_instances(self, instances: Vec<InstanceStats>) -> Vec<InstanceStats> {
+        match self {
+            Self::OwnConnection => Vec::new(),
+            Self::SameUser => instances
+                .into_iter()
+                .filter(|instance| owned_by_current_user(&UsageTracker::get_stats_file_path(&instance.instance_id)))
+                .collect(),
+            Self::Admin => instances,
+        }
+    }
+
+    fn denied(self, reason: &str) -> McpError {
+        let location = Self::config_path()
+            .map_or_else(|| ACCESS_CONFIG_FILE.to_string(), |path| path.display().to_string());
+        McpError::PermissionDenied(format!(
+            "{reason} (access policy '{}', configured in {location})",
+            self.as_str()
+        ))
+    }
+}
+
+/// Whether `connection_id` appears in a stats file owned by the user running this process
+fn connection_owned_by_current_user(connection_id: &str) -> bool {
+    let Some(Ok(entries)) = UsageTracker::stats_dir().map(std::fs::read_dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        let file_name = entry.file_name();
+        let Some(instance_id) = file_name.to_str().and_then(stats_instance_id) else {
+            return false;
+        };
+        owned_by_current_user(&path)
+            && std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|contents| UsageTracker::parse_instance(instance_id, &contents))
+                .is_some_and(|instance| instance.connections.contains_key(connection_id))
+    })
+}
+
+/// Whether a file is owned by the user running this process
+///
+/// Missing files count as owned so that lookups report "not found" rather
+/// than a denial; platforms without Unix ownership always count as owned.
+fn owned_by_current_user(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        std::fs::metadata(path).map_or(true, |metadata| metadata.uid() == nix::unistd::getuid().as_raw())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        true
+    }
+}
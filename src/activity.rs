@@ -0,0 +1,215 @@
+//! Classification of recorded tool calls into agent activities
+//!
+//! History records only carry a tool name and JSON arguments. This module
+//! turns them into what the agent actually did: which files it read or
+//! changed, which commands it ran and what it searched for.
+
+use kodegen_mcp_schema::ToolMetadata;
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+
+use crate::history::RecordedCall;
+
+/// Path arguments per tool, derived once from each tool's args schema
+static PATH_ARGS_BY_TOOL: LazyLock<RwLock<HashMap<String, Arc<PathArgs>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Argument names that hold a single file or directory path
+const PATH_ARGS: &[&str] = &["path", "file_path", "source", "destination", "directory", "dir"];
+
+/// Argument names that hold a list of paths
+const PATH_LIST_ARGS: &[&str] = &["paths", "file_paths"];
+
+/// Argument names that hold a command line
+const COMMAND_ARGS: &[&str] = &["command", "cmd", "input"];
+
+/// Argument names that hold a search query
+const SEARCH_ARGS: &[&str] = &["pattern", "query", "search_term", "search"];
+
+/// Something an agent did in a single tool call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Activity {
+    /// Read a file or directory
+    Read(String),
+    /// Created, overwrote, moved or deleted a file or directory
+    Write(String),
+    /// Edited a file in place
+    Edit(String),
+    /// Ran a command
+    Command(String),
+    /// Searched for a pattern
+    Search(String),
+}
+
+/// Look up registered metadata for a tool
+#[must_use]
+pub fn tool_metadata(tool_name: &str) -> Option<&'static ToolMetadata> {
+    inventory::iter::<ToolMetadata>().find(|tool| tool.name == tool_name)
+}
+
+/// Category name of a tool (None for tools without registered metadata)
+#[must_use]
+pub fn tool_category(tool_name: &str) -> Option<&'static str> {
+    tool_metadata(tool_name).map(|tool| tool.category.name)
+}
+
+/// Whether a tool belongs to the terminal or process category
+#[must_use]
+pub fn is_command_tool(tool_name: &str) -> bool {
+    tool_category(tool_name).is_some_and(|category| {
+        category == kodegen_config::CATEGORY_TERMINAL.name
+            || category == kodegen_config::CATEGORY_PROCESS.name
+    })
+}
+
+/// Classify a recorded call into the activities it represents
+#[must_use]
+pub fn classify(call: &RecordedCall) -> Vec<Activity> {
+    let args = call.args();
+    let tool_name = call.record.tool_name.as_str();
+
+    if is_command_tool(tool_name)
+        && let Some(command) = first_string(&args, COMMAND_ARGS)
+    {
+        return vec![Activity::Command(command)];
+    }
+
+    if tool_name.contains("search")
+        && let Some(query) = first_string(&args, SEARCH_ARGS)
+    {
+        return vec![Activity::Search(query)];
+    }
+
+    let paths = paths(tool_name, &args);
+    if paths.is_empty() {
+        return Vec::new();
+    }
+
+    let make: fn(String) -> Activity = if tool_name.contains("edit") {
+        Activity::Edit
+    } else if ["write", "create", "move", "delete", "rename", "remove"]
+        .iter()
+        .any(|verb| tool_name.contains(verb))
+    {
+        Activity::Write
+    } else {
+        Activity::Read
+    };
+
+    paths.into_iter().map(make).collect()
+}
+
+/// Names of a tool's arguments that hold paths
+#[derive(Debug, Default)]
+struct PathArgs {
+    single: Vec<String>,
+    list: Vec<String>,
+}
+
+/// Extract every path argument of a call to `tool_name`
+///
+/// Path arguments are discovered from the tool's registered args schema, so
+/// tools that name their path argument differently are still indexed. Tools
+/// without metadata fall back to common argument names.
+#[must_use]
+pub fn paths(tool_name: &str, args: &Value) -> Vec<String> {
+    let path_args = path_args(tool_name);
+
+    let mut paths: Vec<String> = path_args
+        .single
+        .iter()
+        .filter_map(|key| args.get(key).and_then(Value::as_str))
+        .map(str::to_string)
+        .collect();
+
+    for key in &path_args.list {
+        if let Some(list) = args.get(key).and_then(Value::as_array) {
+            paths.extend(list.iter().filter_map(Value::as_str).map(str::to_string));
+        }
+    }
+
+    paths
+}
+
+fn path_args(tool_name: &str) -> Arc<PathArgs> {
+    if let Some(cached) = PATH_ARGS_BY_TOOL.read().get(tool_name) {
+        return Arc::clone(cached);
+    }
+
+    let discovered = Arc::new(
+        tool_metadata(tool_name)
+            .map(|tool| path_args_from_schema(&(tool.args_schema)()))
+            .filter(|args| !args.single.is_empty() || !args.list.is_empty())
+            .unwrap_or_else(|| PathArgs {
+                single: PATH_ARGS.iter().map(|s| s.to_string()).collect(),
+                list: PATH_LIST_ARGS.iter().map(|s| s.to_string()).collect(),
+            }),
+    );
+
+    PATH_ARGS_BY_TOOL
+        .write()
+        .insert(tool_name.to_string(), Arc::clone(&discovered));
+    discovered
+}
+
+/// Find top-level properties of an args schema that hold paths
+fn path_args_from_schema(schema: &Value) -> PathArgs {
+    let mut path_args = PathArgs::default();
+
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return path_args;
+    };
+
+    for (name, property) in properties {
+        if !is_path_property(name, property) {
+            continue;
+        }
+
+        if schema_has_type(property, "array") {
+            path_args.list.push(name.clone());
+        } else if schema_has_type(property, "string") {
+            path_args.single.push(name.clone());
+        }
+    }
+
+    path_args
+}
+
+fn is_path_property(name: &str, property: &Value) -> bool {
+    if PATH_ARGS.contains(&name) || PATH_LIST_ARGS.contains(&name) {
+        return true;
+    }
+
+    if ["_path", "_paths", "_file", "_dir"].iter().any(|suffix| name.ends_with(suffix)) {
+        return true;
+    }
+
+    property
+        .get("description")
+        .and_then(Value::as_str)
+        .map(str::to_lowercase)
+        .is_some_and(|description| {
+            ["path to", "file path", "directory path", "absolute path"]
+                .iter()
+                .any(|phrase| description.contains(phrase))
+        })
+}
+
+/// Whether a property schema allows `ty` (handles `"type": [..]` unions)
+fn schema_has_type(property: &Value, ty: &str) -> bool {
+    match property.get("type") {
+        Some(Value::String(t)) => t == ty,
+        Some(Value::Array(types)) => types.iter().any(|t| t.as_str() == Some(ty)),
+        _ => false,
+    }
+}
+
+/// First non-empty string argument among `keys`
+fn first_string(args: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| args.get(key).and_then(Value::as_str))
+        .find(|value| !value.trim().is_empty())
+        .map(str::to_string)
+}
@@ -0,0 +1,760 @@
+//! Declarative alert rules on tool usage
+//!
+//! The server's background evaluator ([`start_evaluator`]) polls the history
+//! of every recently active connection from kodegend and evaluates the rules
+//! per connection over a sliding window of its recent calls. A connection is
+//! dropped once its stats show no call within the longest rule window and
+//! its alerts have resolved. Fired alerts are persisted in
+//! [`ALERTS_STATE_FILE`] (so `inspect_alerts` can read them) and optionally
+//! appended to a JSONL alert log.
+//!
+//! Rules are read from `introspection-alerts.json`, looked up in
+//! `${git_root}/.kodegen/` first and then in the user config directory:
+//!
+//! ```json
+//! {
+//!   "rules": [
+//!     { "name": "failing", "kind": "failure_rate", "above": 20.0, "window_secs": 300 },
+//!     { "name": "slow-builds", "kind": "latency", "tools": ["terminal"], "percentile": 99.0, "above_ms": 30000 },
+//!     { "name": "mass-delete", "kind": "call_count", "tools": ["fs_delete_file"], "above": 20 }
+//!   ],
+//!   "log_file": "/var/log/kodegen/alerts.jsonl"
+//! }
+//! ```
+//!
+//! An alert fires when its condition starts to hold and resolves once it no
+//! longer does; it does not fire again while it stays active.
+
+use kodegen_mcp_schema::McpError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use kodegen_server_http::ShutdownHook;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::stream::{CallFilter, CallWatcher};
+use crate::usage_tracker::UsageTracker;
+
+/// Config file name, resolved with local > user precedence
+pub const ALERTS_CONFIG_FILE: &str = "introspection-alerts.json";
+
+/// File in the kodegen data directory holding the fired alerts of every connection
+pub const ALERTS_STATE_FILE: &str = "alerts.json";
+
+/// Number of most recent alerts kept per connection
+pub const MAX_ALERTS: usize = 100;
+
+// ============================================================================
+// CONFIGURATION
+// ============================================================================
+
+/// Alerting settings from `introspection-alerts.json`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AlertConfig {
+    /// Rules to evaluate
+    pub rules: Vec<AlertRule>,
+    /// Append fired and resolved alerts to this JSONL file
+    pub log_file: Option<PathBuf>,
+    /// Seconds between rule evaluations
+    pub eval_interval_secs: u64,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            log_file: None,
+            eval_interval_secs: 10,
+        }
+    }
+}
+
+impl AlertConfig {
+    /// Load the config file, or no rules when there is none
+    ///
+    /// A file that exists but cannot be read or parsed is an error rather
+    /// than silently disabling the user's rules.
+    pub fn load() -> Result<Self, McpError> {
+        let Ok(path) = kodegen_config::KodegenConfig::resolve_config_file(ALERTS_CONFIG_FILE) else {
+            return Ok(Self::default());
+        };
+
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to read {}: {}", path.display(), e)))?;
+        serde_json::from_str(&text)
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Invalid alert config {}: {}", path.display(), e)))
+    }
+}
+
+/// Default value for window_secs
+fn default_window_secs() -> u64 {
+    300
+}
+
+/// Default value for percentile
+fn default_percentile() -> f64 {
+    99.0
+}
+
+/// A named condition over the calls of the last `window_secs` seconds
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AlertRule {
+    /// Unique rule name, reported with every alert
+    pub name: String,
+    /// Only consider calls of these tools (empty: all tools)
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Length of the sliding window in seconds
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+    #[serde(flatten)]
+    pub condition: AlertCondition,
+}
+
+/// What an [`AlertRule`] checks
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// Percentage of failed calls exceeds `above`
+    FailureRate {
+        above: f64,
+        /// Calls needed in the window before the rate is meaningful
+        #[serde(default)]
+        min_calls: u64,
+    },
+    /// The given latency percentile exceeds `above_ms`
+    Latency {
+        #[serde(default = "default_percentile")]
+        percentile: f64,
+        above_ms: u64,
+    },
+    /// More than `above` calls
+    CallCount { above: u64 },
+}
+
+impl AlertCondition {
+    /// Name used in configuration and reports
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::FailureRate { .. } => "failure_rate",
+            Self::Latency { .. } => "latency",
+            Self::CallCount { .. } => "call_count",
+        }
+    }
+}
+
+impl AlertRule {
+    fn applies_to(&self, tool_name: &str) -> bool {
+        self.tools.is_empty() || self.tools.iter().any(|tool| tool == tool_name)
+    }
+}
+
+// ============================================================================
+// FIRED ALERTS
+// ============================================================================
+
+/// An alert raised by a rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FiredAlert {
+    /// Name of the rule that fired
+    pub rule: String,
+    /// Rule kind: "failure_rate", "latency" or "call_count"
+    pub kind: String,
+    /// Human-readable description of what was observed
+    pub message: String,
+    /// Observed value (percentage, milliseconds or call count)
+    pub value: f64,
+    /// Threshold the value exceeded
+    pub threshold: f64,
+    /// When the alert fired (Unix timestamp)
+    pub fired_at: i64,
+    /// When the condition stopped holding (Unix timestamp, None while active)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<i64>,
+}
+
+impl FiredAlert {
+    /// Whether the alert's condition still holds
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.resolved_at.is_none()
+    }
+}
+
+/// Keep the `MAX_ALERTS` most recent alerts
+pub fn trim_alerts(alerts: &mut Vec<FiredAlert>) {
+    let excess = alerts.len().saturating_sub(MAX_ALERTS);
+    alerts.drain(..excess);
+}
+
+// ============================================================================
+// ENGINE
+// ============================================================================
+
+/// A completed call seen by the evaluator
+struct CallEvent {
+    at: i64,
+    tool_name: String,
+    failed: bool,
+}
+
+/// A measured call latency
+struct LatencyEvent {
+    at: i64,
+    tool_name: String,
+    duration_ms: u64,
+}
+
+/// Sliding-window rule evaluation state
+#[derive(Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    calls: VecDeque<CallEvent>,
+    latencies: VecDeque<LatencyEvent>,
+    /// Rules currently firing
+    active: HashSet<String>,
+}
+
+/// Changes produced by one evaluation
+#[derive(Debug, Default)]
+pub struct AlertChanges {
+    /// Alerts that started firing
+    pub fired: Vec<FiredAlert>,
+    /// Names of rules whose alerts resolved
+    pub resolved: Vec<String>,
+}
+
+impl AlertEngine {
+    #[must_use]
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            rules,
+            ..Self::default()
+        }
+    }
+
+    /// Whether there are rules to evaluate
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Treat these rules as already firing, e.g. for alerts persisted by a previous run
+    pub fn resume(&mut self, active: impl IntoIterator<Item = String>) {
+        self.active.extend(active);
+    }
+
+    /// Whether any rule is currently firing
+    #[must_use]
+    pub fn is_firing(&self) -> bool {
+        !self.active.is_empty()
+    }
+
+    /// Record a completed call at `at` (Unix timestamp)
+    pub fn record_call(&mut self, tool_name: &str, failed: bool, at: i64) {
+        if !self.is_empty() {
+            self.calls.push_back(CallEvent { at, tool_name: tool_name.to_string(), failed });
+        }
+    }
+
+    /// Record a call latency measured at `at` (Unix timestamp)
+    pub fn record_latency(&mut self, tool_name: &str, duration_ms: u64, at: i64) {
+        if !self.is_empty() {
+            self.latencies.push_back(LatencyEvent { at, tool_name: tool_name.to_string(), duration_ms });
+        }
+    }
+
+    /// Evaluate every rule at `now` (Unix timestamp)
+    pub fn evaluate(&mut self, now: i64) -> AlertChanges {
+        // Drop events no rule can see any more
+        let longest = self.rules.iter().map(|rule| rule.window_secs).max().unwrap_or(0);
+        let horizon = now - i64::try_from(longest).unwrap_or(i64::MAX);
+        while self.calls.front().is_some_and(|event| event.at < horizon) {
+            self.calls.pop_front();
+        }
+        while self.latencies.front().is_some_and(|event| event.at < horizon) {
+            self.latencies.pop_front();
+        }
+
+        let mut changes = AlertChanges::default();
+        for rule in &self.rules {
+            let observed = self.observe(rule, now);
+            let was_active = self.active.contains(&rule.name);
+            let holds = observed.is_some();
+            match observed {
+                Some((value, threshold, message)) if !was_active => {
+                    changes.fired.push(FiredAlert {
+                        rule: rule.name.clone(),
+                        kind: rule.condition.kind().to_string(),
+                        message,
+                        value,
+                        threshold,
+                        fired_at: now,
+                        resolved_at: None,
+                    });
+                }
+                None if was_active => changes.resolved.push(rule.name.clone()),
+                _ => {}
+            }
+            if holds {
+                self.active.insert(rule.name.clone());
+            } else {
+                self.active.remove(&rule.name);
+            }
+        }
+        changes
+    }
+
+    /// (value, threshold, message) if the rule's condition holds
+    fn observe(&self, rule: &AlertRule, now: i64) -> Option<(f64, f64, String)> {
+        let since = now - i64::try_from(rule.window_secs).unwrap_or(i64::MAX);
+        let window = rule.window_secs;
+
+        match rule.condition {
+            AlertCondition::FailureRate { above, min_calls } => {
+                let (total, failed) = self
+                    .calls
+                    .iter()
+                    .filter(|event| event.at >= since && rule.applies_to(&event.tool_name))
+                    .fold((0u64, 0u64), |(total, failed), event| (total + 1, failed + u64::from(event.failed)));
+                if total == 0 || total < min_calls {
+                    return None;
+                }
+                let rate = failed as f64 / total as f64 * 100.0;
+                (rate > above).then(|| {
+                    (rate, above, format!("{failed} of {total} calls failed in the last {window}s ({rate:.1}% > {above:.1}%)"))
+                })
+            }
+            AlertCondition::Latency { percentile, above_ms } => {
+                let mut durations: Vec<u64> = self
+                    .latencies
+                    .iter()
+                    .filter(|event| event.at >= since && rule.applies_to(&event.tool_name))
+                    .map(|event| event.duration_ms)
+                    .collect();
+                let value = nearest_rank(&mut durations, percentile)?;
+                (value > above_ms).then(|| {
+                    (
+                        value as f64,
+                        above_ms as f64,
+                        format!("p{percentile} latency {value}ms over {} calls in the last {window}s (> {above_ms}ms)", durations.len()),
+                    )
+                })
+            }
+            AlertCondition::CallCount { above } => {
+                let count = self
+                    .calls
+                    .iter()
+                    .filter(|event| event.at >= since && rule.applies_to(&event.tool_name))
+                    .count() as u64;
+                (count > above).then(|| {
+                    (count as f64, above as f64, format!("{count} calls in the last {window}s (> {above})"))
+                })
+            }
+        }
+    }
+}
+
+/// Nearest-rank percentile (None for no samples)
+fn nearest_rank(values: &mut [u64], percentile: f64) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let rank = (percentile.clamp(0.0, 100.0) / 100.0 * values.len() as f64).ceil() as usize;
+    values.get(rank.saturating_sub(1)).copied()
+}
+
+/// Append alert state changes to the JSONL alert log
+pub async fn append_to_log(path: &PathBuf, entries: &[serde_json::Value]) {
+    use tokio::io::AsyncWriteExt;
+
+    if entries.is_empty() {
+        return;
+    }
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&entry.to_string());
+        lines.push('\n');
+    }
+
+    if let Some(parent) = path.parent() {
+        let _ = tokio::fs::create_dir_all(parent).await;
+    }
+    let file = tokio::fs::OpenOptions::new().create(true).append(true).open(path).await;
+    match file {
+        Ok(mut file) => {
+            if let Err(e) = file.write_all(lines.as_bytes()).await {
+                log::error!("Failed to write alert log {}: {}", path.display(), e);
+            }
+        }
+        Err(e) => log::error!("Failed to open alert log {}: {}", path.display(), e),
+    }
+}
+
+// ============================================================================
+// PERSISTED ALERTS
+// ============================================================================
+
+fn state_path() -> Option<PathBuf> {
+    kodegen_config::KodegenConfig::data_dir().ok().map(|dir| dir.join(ALERTS_STATE_FILE))
+}
+
+/// Fired alerts of every connection, oldest first (empty if none were persisted)
+pub async fn load_alerts() -> BTreeMap<String, Vec<FiredAlert>> {
+    let Some(path) = state_path() else {
+        return BTreeMap::new();
+    };
+    match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid alert state {}: {}", path.display(), e);
+            BTreeMap::new()
+        }),
+        Err(_) => BTreeMap::new(),
+    }
+}
+
+async fn save_alerts(alerts: &BTreeMap<String, Vec<FiredAlert>>) {
+    let Some(path) = state_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = tokio::fs::create_dir_all(parent).await;
+    }
+    let json = serde_json::to_string_pretty(alerts).unwrap_or_default();
+    if let Err(e) = tokio::fs::write(&path, json).await {
+        log::error!("Failed to write alert state to {}: {}", path.display(), e);
+    }
+}
+
+/// Store one connection's alert state changes, returning the alert log entries for them
+fn apply_changes(alerts: &mut Vec<FiredAlert>, connection_id: &str, changes: AlertChanges, now: i64) -> Vec<serde_json::Value> {
+    let mut entries = Vec::new();
+    for rule in changes.resolved {
+        if let Some(alert) = alerts.iter_mut().rev().find(|alert| alert.rule == rule && alert.is_active()) {
+            alert.resolved_at = Some(now);
+            entries.push(serde_json::json!({ "event": "resolved", "connection_id": connection_id, "alert": alert }));
+        }
+    }
+    for alert in changes.fired {
+        log::warn!("Alert '{}' fired for connection {}: {}", alert.rule, connection_id, alert.message);
+        entries.push(serde_json::json!({ "event": "fired", "connection_id": connection_id, "alert": alert }));
+        alerts.push(alert);
+    }
+    trim_alerts(alerts);
+    entries
+}
+
+// ============================================================================
+// BACKGROUND EVALUATION
+// ============================================================================
+
+/// Handle to the background rule evaluator
+pub struct AlertHandle {
+    shutdown: tokio::sync::oneshot::Sender<()>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl AlertHandle {
+    /// Stop the evaluator
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(());
+        let _ = self.task.await;
+    }
+
+    /// Hook that stops the evaluator when the MCP server shuts down its managers
+    #[must_use]
+    pub fn into_shutdown_hook(self) -> AlertShutdown {
+        AlertShutdown(tokio::sync::Mutex::new(Some(self)))
+    }
+}
+
+/// [`ShutdownHook`] owning a running alert evaluator
+pub struct AlertShutdown(tokio::sync::Mutex<Option<AlertHandle>>);
+
+impl ShutdownHook for AlertShutdown {
+    fn shutdown(&self) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + '_>> {
+        Box::pin(async move {
+            if let Some(handle) = self.0.lock().await.take() {
+                handle.shutdown().await;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Rule evaluation state of one connection
+struct ConnectionAlerts {
+    watcher: CallWatcher,
+    engine: AlertEngine,
+}
+
+/// Evaluation state of every known connection
+struct Evaluator {
+    config: AlertConfig,
+    connections: HashMap<String, ConnectionAlerts>,
+    alerts: BTreeMap<String, Vec<FiredAlert>>,
+}
+
+impl Evaluator {
+    async fn new(config: AlertConfig) -> Self {
+        let mut alerts = load_alerts().await;
+
+        // Alerts of rules removed from the config can no longer resolve on their own
+        let now = chrono::Utc::now().timestamp();
+        for alert in alerts.values_mut().flatten() {
+            if alert.is_active() && !config.rules.iter().any(|rule| rule.name == alert.rule) {
+                alert.resolved_at = Some(now);
+            }
+        }
+
+        Self { config, connections: HashMap::new(), alerts }
+    }
+
+    /// Start evaluating a connection, replaying the longest rule window of its history
+    fn watch(&mut self, connection_id: String, now: i64) {
+        let Self { config, connections, alerts } = self;
+        connections.entry(connection_id).or_insert_with_key(|connection_id| {
+            let since = chrono::DateTime::from_timestamp(now - i64::try_from(longest_window(config)).unwrap_or(i64::MAX), 0);
+
+            let mut engine = AlertEngine::new(config.rules.clone());
+            if let Some(alerts) = alerts.get(connection_id) {
+                engine.resume(alerts.iter().filter(|alert| alert.is_active()).map(|alert| alert.rule.clone()));
+            }
+            ConnectionAlerts {
+                watcher: CallWatcher::new(vec![connection_id.clone()], CallFilter { tool_name: None, since }),
+                engine,
+            }
+        });
+    }
+
+    /// Feed new calls to every connection's rules and evaluate them at `now`
+    ///
+    /// Queries kodegend synchronously; returns the alert log entries of the changes.
+    fn evaluate(&mut self, now: i64) -> Vec<serde_json::Value> {
+        let mut entries = Vec::new();
+        for (connection_id, state) in &mut self.connections {
+            match state.watcher.poll() {
+                Ok(calls) => {
+                    for call in calls {
+                        let at = call.time.map_or(now, |time| time.timestamp());
+                        state.engine.record_call(&call.record.tool_name, call.is_failure(), at);
+                        if let Some(duration_ms) = call.record.duration_ms {
+                            state.engine.record_latency(&call.record.tool_name, duration_ms, at);
+                        }
+                    }
+                }
+                // Disconnected connections keep being evaluated so their alerts resolve
+                Err(e) => log::debug!("No new calls for alert rules of connection {connection_id}: {e}"),
+            }
+
+            let changes = state.engine.evaluate(now);
+            if changes.fired.is_empty() && changes.resolved.is_empty() {
+                continue;
+            }
+            let alerts = self.alerts.entry(connection_id.clone()).or_default();
+            entries.extend(apply_changes(alerts, connection_id, changes, now));
+        }
+        entries
+    }
+
+    /// One evaluation round over the connections currently known
+    ///
+    /// Returns None if the blocking evaluation could not complete (runtime shutdown).
+    async fn round(mut self) -> Option<Self> {
+        let now = chrono::Utc::now().timestamp();
+
+        // Only connections with calls a rule window can still see need polling
+        let idle_after = longest_window(&self.config).saturating_add(self.config.eval_interval_secs.max(1));
+        let mut wanted: HashSet<String> = recent_connections(now - i64::try_from(idle_after).unwrap_or(i64::MAX)).await;
+
+        // Connections with firing alerts stay until the alerts resolve
+        wanted.extend(
+            self.alerts
+                .iter()
+                .filter(|(_, alerts)| alerts.iter().any(FiredAlert::is_active))
+                .map(|(connection_id, _)| connection_id.clone()),
+        );
+        self.connections.retain(|connection_id, state| wanted.contains(connection_id) || state.engine.is_firing());
+        for connection_id in wanted {
+            self.watch(connection_id, now);
+        }
+
+        let (evaluator, entries) = tokio::task::spawn_blocking(move || {
+            let entries = self.evaluate(now);
+            (self, entries)
+        })
+        .await
+        .ok()?;
+
+        if !entries.is_empty() {
+            save_alerts(&evaluator.alerts).await;
+            if let Some(log_file) = &evaluator.config.log_file {
+                append_to_log(log_file, &entries).await;
+            }
+        }
+        Some(evaluator)
+    }
+}
+
+/// Longest sliding window of any rule, in seconds
+fn longest_window(config: &AlertConfig) -> u64 {
+    config.rules.iter().map(|rule| rule.window_secs).max().unwrap_or(0)
+}
+
+/// Connections whose stats files record a call at or after `since` (Unix timestamp)
+///
+/// The evaluator works for the server itself rather than a requester, so it
+/// reads every stats file.
+async fn recent_connections(since: i64) -> HashSet<String> {
+    UsageTracker::load_all_instances()
+        .await
+        .into_iter()
+        .flat_map(|instance| instance.connections)
+        .filter(|(_, stats)| stats.last_used >= since)
+        .map(|(connection_id, _)| connection_id)
+        .collect()
+}
+
+/// Evaluate `config`'s rules over every active connection's calls every `eval_interval_secs`
+#[must_use]
+pub fn start_evaluator(config: AlertConfig) -> AlertHandle {
+    let interval = Duration::from_secs(config.eval_interval_secs.max(1));
+    let rules = config.rules.len();
+    let (shutdown, mut shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+
+    let task = tokio::spawn(async move {
+        let mut evaluator = Evaluator::new(config).await;
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = &mut shutdown_rx => break,
+            }
+            match evaluator.round().await {
+                Some(next) => evaluator = next,
+                None => break,
+            }
+        }
+    });
+
+    log::info!("Evaluating {rules} alert rules every {}s", interval.as_secs());
+
+    AlertHandle { shutdown, task }
+}
+
+/// Start the evaluator if `introspection-alerts.json` defines any rules
+pub fn start_from_config() -> anyhow::Result<Option<AlertHandle>> {
+    let config = AlertConfig::load().map_err(|e| anyhow::anyhow!("{e}"))?;
+    if config.rules.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(start_evaluator(config)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, condition: AlertCondition) -> AlertRule {
+        AlertRule { name: name.to_string(), tools: Vec::new(), window_secs: 60, condition }
+    }
+
+    #[test]
+    fn alert_fires_stays_active_and_resolves() {
+        let mut engine = AlertEngine::new(vec![rule("busy", AlertCondition::CallCount { above: 2 })]);
+        for at in 100..103 {
+            engine.record_call("read_file", false, at);
+        }
+
+        let changes = engine.evaluate(103);
+        assert_eq!(changes.fired.len(), 1);
+        assert_eq!(changes.fired[0].rule, "busy");
+        assert_eq!(changes.fired[0].value, 3.0);
+        assert_eq!(changes.fired[0].fired_at, 103);
+        assert!(changes.resolved.is_empty());
+
+        // Still holding: no second alert
+        engine.record_call("read_file", false, 104);
+        let changes = engine.evaluate(105);
+        assert!(changes.fired.is_empty());
+        assert!(changes.resolved.is_empty());
+
+        // The calls slid out of the window
+        let changes = engine.evaluate(200);
+        assert!(changes.fired.is_empty());
+        assert_eq!(changes.resolved, ["busy"]);
+
+        // Resolved alerts can fire again
+        for at in 300..303 {
+            engine.record_call("read_file", false, at);
+        }
+        assert_eq!(engine.evaluate(303).fired.len(), 1);
+    }
+
+    #[test]
+    fn resumed_alert_does_not_fire_again() {
+        let mut engine = AlertEngine::new(vec![rule("busy", AlertCondition::CallCount { above: 0 })]);
+        engine.resume(["busy".to_string()]);
+        engine.record_call("read_file", false, 100);
+        assert!(engine.evaluate(100).fired.is_empty());
+        assert_eq!(engine.evaluate(500).resolved, ["busy"]);
+    }
+
+    #[test]
+    fn failure_rate_needs_min_calls() {
+        let mut engine = AlertEngine::new(vec![rule("failing", AlertCondition::FailureRate { above: 50.0, min_calls: 3 })]);
+        engine.record_call("terminal", true, 100);
+        engine.record_call("terminal", true, 101);
+        assert!(engine.evaluate(102).fired.is_empty());
+
+        engine.record_call("terminal", false, 102);
+        let fired = engine.evaluate(103).fired;
+        assert_eq!(fired.len(), 1);
+        assert!((fired[0].value - 200.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn latency_uses_nearest_rank_percentile_of_matching_tools() {
+        let mut slow = rule("slow", AlertCondition::Latency { percentile: 50.0, above_ms: 1000 });
+        slow.tools = vec!["terminal".to_string()];
+        let mut engine = AlertEngine::new(vec![slow]);
+        engine.record_latency("terminal", 500, 100);
+        engine.record_latency("terminal", 2000, 101);
+        engine.record_latency("read_file", 9000, 101);
+        assert!(engine.evaluate(102).fired.is_empty());
+
+        engine.record_latency("terminal", 3000, 102);
+        let fired = engine.evaluate(103).fired;
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].value, 2000.0);
+    }
+
+    #[test]
+    fn changes_update_persisted_alerts() {
+        let mut alerts = Vec::new();
+        let fired = FiredAlert {
+            rule: "busy".to_string(),
+            kind: "call_count".to_string(),
+            message: "3 calls".to_string(),
+            value: 3.0,
+            threshold: 2.0,
+            fired_at: 100,
+            resolved_at: None,
+        };
+        let entries = apply_changes(&mut alerts, "conn-1", AlertChanges { fired: vec![fired], resolved: Vec::new() }, 100);
+        assert_eq!(entries[0]["event"], "fired");
+        assert_eq!(entries[0]["connection_id"], "conn-1");
+        assert!(alerts[0].is_active());
+
+        let entries = apply_changes(&mut alerts, "conn-1", AlertChanges { fired: Vec::new(), resolved: vec!["busy".to_string()] }, 160);
+        assert_eq!(entries[0]["event"], "resolved");
+        assert_eq!(alerts[0].resolved_at, Some(160));
+    }
+}
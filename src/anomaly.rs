@@ -0,0 +1,387 @@
+//! Robust anomaly detection over tool call time series
+//!
+//! History is cut into fixed-width buckets, giving three series: calls per
+//! bucket, failures per bucket and, per tool, the median call latency. Each
+//! bucket is compared with a baseline built from the buckets before it: an
+//! exponentially weighted moving average (EWMA) of the values, with the
+//! median absolute deviation (MAD) as a spread estimate that a few earlier
+//! outliers cannot inflate. A bucket is anomalous when it lies more than
+//! `threshold` spreads above the baseline.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::history::RecordedCall;
+
+/// Scales the MAD to a standard deviation for normally distributed data
+const MAD_SCALE: f64 = 1.4826;
+
+/// Most buckets a history may be split into
+pub const MAX_BUCKETS: usize = 10_000;
+
+/// Tool calls that completed within one bucket
+#[derive(Debug, Clone)]
+pub struct Bucket {
+    pub start: DateTime<Utc>,
+    pub calls: u64,
+    pub failures: u64,
+    /// Recorded durations by tool, in milliseconds
+    pub durations: BTreeMap<String, Vec<u64>>,
+}
+
+/// Split chronological calls into contiguous buckets of `width`
+///
+/// Buckets without calls in between are kept (with zero calls) so quiet
+/// periods count towards the baseline. Calls without a parseable timestamp
+/// are skipped. A span needing more than [`MAX_BUCKETS`] buckets is refused
+/// with the smallest width that fits, in seconds.
+pub fn bucketize(calls: &[RecordedCall], width: Duration) -> Result<Vec<Bucket>, u64> {
+    let width_secs = i64::try_from(width.as_secs().max(1)).unwrap_or(i64::MAX);
+    let timed: Vec<(i64, &RecordedCall)> = calls
+        .iter()
+        .filter_map(|call| call.time.map(|time| (time.timestamp(), call)))
+        .collect();
+    let (Some(first), Some(last)) = (timed.iter().map(|(at, _)| *at).min(), timed.iter().map(|(at, _)| *at).max()) else {
+        return Ok(Vec::new());
+    };
+
+    let origin = first - first.rem_euclid(width_secs);
+    let count = usize::try_from((last - origin) / width_secs + 1).unwrap_or(0);
+    if count > MAX_BUCKETS {
+        // Aligning the origin down can add one bucket, so leave room for it
+        let span = u64::try_from(last - first).unwrap_or(u64::MAX);
+        return Err(span / (MAX_BUCKETS as u64 - 1) + 1);
+    }
+    let mut buckets: Vec<Bucket> = (0..count)
+        .map(|i| Bucket {
+            start: DateTime::from_timestamp(origin + i as i64 * width_secs, 0).unwrap_or_default(),
+            calls: 0,
+            failures: 0,
+            durations: BTreeMap::new(),
+        })
+        .collect();
+
+    for (at, call) in timed {
+        let Some(bucket) = usize::try_from((at - origin) / width_secs).ok().and_then(|i| buckets.get_mut(i)) else {
+            continue;
+        };
+        bucket.calls += 1;
+        if call.is_failure() {
+            bucket.failures += 1;
+        }
+        if let Some(duration_ms) = call.record.duration_ms {
+            bucket.durations.entry(call.record.tool_name.clone()).or_default().push(duration_ms);
+        }
+    }
+    Ok(buckets)
+}
+
+// ============================================================================
+// DETECTION
+// ============================================================================
+
+/// Baseline and sensitivity settings
+#[derive(Debug, Clone, Copy)]
+pub struct DetectorConfig {
+    /// EWMA smoothing factor (weight of the most recent bucket)
+    pub alpha: f64,
+    /// Number of preceding buckets the baseline is built from
+    pub baseline_buckets: usize,
+    /// Buckets with a value needed before a baseline is trusted
+    pub min_baseline: usize,
+    /// Robust z-score above which a bucket is anomalous
+    pub threshold: f64,
+}
+
+/// What kind of deviation was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Many more calls than usual
+    CallRateSpike,
+    /// A tool's median latency far above its rolling baseline
+    LatencyRegression,
+    /// Many more failed calls than usual
+    FailureBurst,
+}
+
+impl AnomalyKind {
+    /// Name used in reports
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CallRateSpike => "call_rate_spike",
+            Self::LatencyRegression => "latency_regression",
+            Self::FailureBurst => "failure_burst",
+        }
+    }
+}
+
+/// An anomalous interval with the numbers behind it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Anomaly {
+    /// Kind of deviation
+    pub kind: AnomalyKind,
+    /// Tool the series belongs to (latency regressions only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// Start of the interval (ISO 8601)
+    pub start: String,
+    /// End of the interval (ISO 8601, exclusive)
+    pub end: String,
+    /// Number of consecutive anomalous buckets merged into the interval
+    pub buckets: usize,
+    /// Peak observed value (calls, failures or median latency in ms per bucket)
+    pub value: f64,
+    /// EWMA baseline at the peak
+    pub baseline: f64,
+    /// Robust spread (scaled MAD) at the peak
+    pub spread: f64,
+    /// Peak robust z-score: (value - baseline) / spread
+    pub score: f64,
+}
+
+/// Flag anomalous buckets in all three series, strongest first
+#[must_use]
+pub fn detect(buckets: &[Bucket], width: Duration, config: &DetectorConfig) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+
+    let calls: Vec<Option<f64>> = buckets.iter().map(|bucket| Some(bucket.calls as f64)).collect();
+    anomalies.extend(scan(buckets, width, &calls, config, AnomalyKind::CallRateSpike, None, |_| 1.0));
+
+    let failures: Vec<Option<f64>> = buckets.iter().map(|bucket| Some(bucket.failures as f64)).collect();
+    anomalies.extend(scan(buckets, width, &failures, config, AnomalyKind::FailureBurst, None, |_| 1.0));
+
+    let tools: std::collections::BTreeSet<&String> = buckets.iter().flat_map(|bucket| bucket.durations.keys()).collect();
+    for tool_name in tools {
+        let latencies: Vec<Option<f64>> = buckets
+            .iter()
+            .map(|bucket| bucket.durations.get(tool_name).and_then(|durations| median_u64(durations)))
+            .collect();
+        // Latency noise scales with the latency itself
+        anomalies.extend(scan(
+            buckets,
+            width,
+            &latencies,
+            config,
+            AnomalyKind::LatencyRegression,
+            Some(tool_name),
+            |baseline| (baseline * 0.1).max(1.0),
+        ));
+    }
+
+    anomalies.sort_by(|a, b| b.score.total_cmp(&a.score));
+    anomalies
+}
+
+/// Flag buckets of one series, merging consecutive ones into intervals
+fn scan(
+    buckets: &[Bucket],
+    width: Duration,
+    values: &[Option<f64>],
+    config: &DetectorConfig,
+    kind: AnomalyKind,
+    tool_name: Option<&String>,
+    min_spread: impl Fn(f64) -> f64,
+) -> Vec<Anomaly> {
+    let width = chrono::Duration::from_std(width).unwrap_or_default();
+    let mut anomalies: Vec<Anomaly> = Vec::new();
+    let mut previous_flagged = false;
+
+    for (i, value) in values.iter().enumerate() {
+        let Some(value) = *value else {
+            previous_flagged = false;
+            continue;
+        };
+        let history: Vec<f64> = values[i.saturating_sub(config.baseline_buckets)..i].iter().flatten().copied().collect();
+        if history.len() < config.min_baseline.max(1) {
+            previous_flagged = false;
+            continue;
+        }
+
+        let baseline = ewma(&history, config.alpha);
+        let spread = (mad(&history) * MAD_SCALE).max(min_spread(baseline));
+        let score = (value - baseline) / spread;
+        if score < config.threshold {
+            previous_flagged = false;
+            continue;
+        }
+
+        let end = (buckets[i].start + width).to_rfc3339();
+        match anomalies.last_mut() {
+            Some(last) if previous_flagged => {
+                last.end = end;
+                last.buckets += 1;
+                if score > last.score {
+                    last.value = value;
+                    last.baseline = baseline;
+                    last.spread = spread;
+                    last.score = score;
+                }
+            }
+            _ => anomalies.push(Anomaly {
+                kind,
+                tool_name: tool_name.cloned(),
+                start: buckets[i].start.to_rfc3339(),
+                end,
+                buckets: 1,
+                value,
+                baseline,
+                spread,
+                score,
+            }),
+        }
+        previous_flagged = true;
+    }
+    anomalies
+}
+
+/// Exponentially weighted moving average, seeded with the first value
+fn ewma(values: &[f64], alpha: f64) -> f64 {
+    let mut iter = values.iter();
+    let Some(first) = iter.next() else {
+        return 0.0;
+    };
+    iter.fold(*first, |average, value| alpha * value + (1.0 - alpha) * average)
+}
+
+/// Median absolute deviation from the median
+fn mad(values: &[f64]) -> f64 {
+    let Some(center) = median(values) else {
+        return 0.0;
+    };
+    let deviations: Vec<f64> = values.iter().map(|value| (value - center).abs()).collect();
+    median(&deviations).unwrap_or(0.0)
+}
+
+fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    Some(if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    })
+}
+
+fn median_u64(values: &[u64]) -> Option<f64> {
+    median(&values.iter().map(|value| *value as f64).collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kodegen_mcp_schema::introspection::ToolCallRecord;
+
+    fn call(timestamp: &str, duration_ms: u64, output_json: &str) -> RecordedCall {
+        RecordedCall {
+            server: "filesystem".to_string(),
+            time: crate::history::parse_timestamp(timestamp),
+            connection_id: None,
+            record: ToolCallRecord {
+                tool_name: "fs_read_file".to_string(),
+                timestamp: timestamp.to_string(),
+                duration_ms: Some(duration_ms),
+                args_json: "{}".to_string(),
+                output_json: output_json.to_string(),
+            },
+        }
+    }
+
+    fn buckets(calls: &[u64]) -> Vec<Bucket> {
+        calls
+            .iter()
+            .enumerate()
+            .map(|(i, calls)| Bucket {
+                start: DateTime::from_timestamp(i as i64 * 60, 0).unwrap(),
+                calls: *calls,
+                failures: 0,
+                durations: BTreeMap::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bucketize_keeps_quiet_buckets() {
+        let calls = [
+            call("2024-10-12T10:00:05Z", 10, "{}"),
+            call("2024-10-12T10:00:30Z", 30, r#"{"error":"not found"}"#),
+            call("2024-10-12T10:03:10Z", 20, "{}"),
+            call("not a timestamp", 40, "{}"),
+        ];
+        let buckets = bucketize(&calls, Duration::from_secs(60)).unwrap();
+
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[0].start.to_rfc3339(), "2024-10-12T10:00:00+00:00");
+        assert_eq!(buckets.iter().map(|bucket| bucket.calls).collect::<Vec<_>>(), [2, 0, 0, 1]);
+        assert_eq!(buckets.iter().map(|bucket| bucket.failures).collect::<Vec<_>>(), [1, 0, 0, 0]);
+        assert_eq!(buckets[0].durations["fs_read_file"], [10, 30]);
+        assert!(bucketize(&[], Duration::from_secs(60)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn bucketize_caps_bucket_count() {
+        let calls = [
+            call("2024-10-12T10:00:00Z", 10, "{}"),
+            call("2024-11-12T10:00:00Z", 10, "{}"),
+        ];
+        let min_secs = bucketize(&calls, Duration::from_secs(1)).unwrap_err();
+        assert_eq!(min_secs, 268);
+        assert!(bucketize(&calls, Duration::from_secs(min_secs - 1)).is_err());
+        assert!(bucketize(&calls, Duration::from_secs(min_secs)).unwrap().len() <= MAX_BUCKETS);
+    }
+
+    #[test]
+    fn ewma_weights_recent_values() {
+        assert_eq!(ewma(&[], 0.5), 0.0);
+        assert_eq!(ewma(&[4.0], 0.5), 4.0);
+        assert_eq!(ewma(&[4.0, 8.0, 0.0], 0.5), 3.0);
+        assert_eq!(ewma(&[4.0, 8.0], 1.0), 8.0);
+    }
+
+    #[test]
+    fn mad_ignores_outliers() {
+        assert_eq!(median(&[3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(median(&[4.0, 1.0, 2.0, 3.0]), Some(2.5));
+        assert_eq!(median(&[]), None);
+        assert_eq!(mad(&[2.0, 2.0, 2.0, 2.0, 100.0]), 0.0);
+        assert_eq!(mad(&[1.0, 2.0, 3.0, 4.0, 5.0]), 1.0);
+    }
+
+    #[test]
+    fn consecutive_flagged_buckets_merge() {
+        let config = DetectorConfig { alpha: 0.3, baseline_buckets: 6, min_baseline: 3, threshold: 3.0 };
+        let width = Duration::from_secs(60);
+        let anomalies = detect(&buckets(&[2, 2, 2, 2, 2, 2, 10, 12, 2, 20]), width, &config);
+
+        assert_eq!(anomalies.len(), 2);
+        assert!(anomalies.iter().all(|anomaly| anomaly.kind == AnomalyKind::CallRateSpike));
+
+        // Strongest first: the isolated spike at the end
+        assert_eq!(anomalies[0].buckets, 1);
+        assert_eq!(anomalies[0].value, 20.0);
+
+        // Buckets 6 and 7 form one interval, reported at its peak score
+        let merged = &anomalies[1];
+        assert_eq!(merged.buckets, 2);
+        assert_eq!(merged.start, "1970-01-01T00:06:00+00:00");
+        assert_eq!(merged.end, "1970-01-01T00:08:00+00:00");
+        assert_eq!(merged.value, 10.0);
+        assert_eq!(merged.baseline, 2.0);
+        assert_eq!(merged.spread, 1.0);
+        assert_eq!(merged.score, 8.0);
+    }
+
+    #[test]
+    fn short_history_is_not_judged() {
+        let config = DetectorConfig { alpha: 0.3, baseline_buckets: 6, min_baseline: 3, threshold: 3.0 };
+        assert!(detect(&buckets(&[2, 2, 50]), Duration::from_secs(60), &config).is_empty());
+    }
+}
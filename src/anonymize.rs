@@ -0,0 +1,302 @@
+//! Pseudonymization of machine details for shareable reports
+//!
+//! An [`Anonymizer`] replaces home directories, usernames, hostnames and
+//! e-mail addresses with pseudonyms (`user1`, `host1`, `email1@example.com`).
+//! The same original always maps to the same pseudonym within one
+//! anonymizer, so a report stays internally consistent; use a fresh
+//! anonymizer per report so pseudonyms cannot be correlated across reports.
+
+use kodegen_mcp_schema::McpError;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b").expect("valid e-mail regex")
+});
+
+/// `/home/<user>`, `/Users/<user>` and `C:\Users\<user>` (also with escaped backslashes)
+static HOME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?P<prefix>/home/|/Users/|[A-Za-z]:(?:\\\\|\\)Users(?:\\\\|\\))(?P<user>[^/\\\s"':]+)"#)
+        .expect("valid home directory regex")
+});
+
+/// Usernames this short or this generic are only replaced inside home paths
+const MIN_NAME_LEN: usize = 3;
+const GENERIC_USERS: &[&str] = &["root", "admin", "user", "users", "shared", "public", "guest"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    User,
+    Host,
+    Email,
+}
+
+/// Replaces identifying machine details with stable pseudonyms
+pub struct Anonymizer {
+    /// Home directory of the current user and the path up to its last component
+    home: Option<(Regex, String)>,
+    /// Usernames and hostnames replaced wherever they appear as words
+    names: Vec<(Kind, String)>,
+    names_regex: Option<Regex>,
+    pseudonyms: HashMap<(Kind, String), String>,
+    counters: HashMap<Kind, usize>,
+}
+
+impl Default for Anonymizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Anonymizer {
+    /// Anonymizer seeded with the current user, home directory and hostname
+    #[must_use]
+    pub fn new() -> Self {
+        let mut anonymizer = Self {
+            home: None,
+            names: Vec::new(),
+            names_regex: None,
+            pseudonyms: HashMap::new(),
+            counters: HashMap::new(),
+        };
+
+        if let Some(user) = current_user() {
+            anonymizer.learn(Kind::User, &user);
+            let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")).ok();
+            if let Some(home) = home.filter(|home| home.len() > 1) {
+                let home = home.trim_end_matches(['/', '\\']);
+                let prefix = home.rfind(['/', '\\']).map_or("", |end| &home[..=end]);
+                anonymizer.home = Regex::new(&format!(r"{}\b", regex::escape(home)))
+                    .ok()
+                    .map(|pattern| (pattern, prefix.to_string()));
+            }
+        }
+        for host in current_hostnames() {
+            anonymizer.learn(Kind::Host, &host);
+        }
+
+        anonymizer
+    }
+
+    /// Anonymize free text
+    pub fn text(&mut self, text: &str) -> String {
+        let text = EMAIL.replace_all(text, |caps: &Captures| self.pseudonym(Kind::Email, &caps[0]));
+
+        let mut discovered = Vec::new();
+        let text = HOME.replace_all(&text, |caps: &Captures| {
+            discovered.push(caps["user"].to_string());
+            format!("{}{}", &caps["prefix"], self.pseudonym(Kind::User, &caps["user"]))
+        });
+        let text = text.into_owned();
+        for user in discovered {
+            self.learn(Kind::User, &user);
+        }
+
+        // Homes outside the usual locations (e.g. /root), after HOME so they are not rewritten twice;
+        // only the last component is replaced so the path keeps its platform layout
+        let text = match self.home.clone() {
+            Some((home, prefix)) => {
+                let user = current_user().unwrap_or_default();
+                let replacement = format!("{prefix}{}", self.pseudonym(Kind::User, &user));
+                home.replace_all(&text, regex::NoExpand(&replacement)).into_owned()
+            }
+            None => text,
+        };
+
+        match self.names_regex.clone() {
+            Some(names) => names
+                .replace_all(&text, |caps: &Captures| {
+                    let name = &caps[0];
+                    let kind = self.names
+                        .iter()
+                        .find(|(_, known)| known.eq_ignore_ascii_case(name))
+                        .map_or(Kind::User, |(kind, _)| *kind);
+                    self.pseudonym(kind, name)
+                })
+                .into_owned(),
+            None => text,
+        }
+    }
+
+    /// Anonymize every string (and object key) in a JSON value
+    pub fn value(&mut self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.text(text),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.value(item)),
+            Value::Object(map) => {
+                let entries = std::mem::take(map);
+                for (key, mut item) in entries {
+                    self.value(&mut item);
+                    map.insert(self.text(&key), item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Anonymize a structured tool output by round-tripping it through JSON
+    pub fn output<T: Serialize + DeserializeOwned>(&mut self, output: T) -> Result<T, McpError> {
+        let mut value = serde_json::to_value(output)?;
+        self.value(&mut value);
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Register a name to be replaced wherever it appears as a word
+    fn learn(&mut self, kind: Kind, name: &str) {
+        if name.len() < MIN_NAME_LEN
+            || (kind == Kind::User && GENERIC_USERS.contains(&name.to_lowercase().as_str()))
+            || self.names.iter().any(|(_, known)| known.eq_ignore_ascii_case(name))
+        {
+            return;
+        }
+        self.names.push((kind, name.to_string()));
+
+        // Longest first, so "alice-mbp.local" wins over "alice-mbp"
+        let mut alternatives: Vec<&str> = self.names.iter().map(|(_, name)| name.as_str()).collect();
+        alternatives.sort_by_key(|name| std::cmp::Reverse(name.len()));
+        let pattern = alternatives.iter().map(|name| regex::escape(name)).collect::<Vec<_>>().join("|");
+        self.names_regex = Regex::new(&format!(r"(?i)\b(?:{pattern})\b")).ok();
+    }
+
+    /// Stable pseudonym for an original value
+    fn pseudonym(&mut self, kind: Kind, original: &str) -> String {
+        let key = (kind, original.to_lowercase());
+        if let Some(pseudonym) = self.pseudonyms.get(&key) {
+            return pseudonym.clone();
+        }
+
+        let n = self.counters.entry(kind).or_default();
+        *n += 1;
+        let pseudonym = match kind {
+            Kind::User => format!("user{n}"),
+            Kind::Host => format!("host{n}"),
+            Kind::Email => format!("email{n}@example.com"),
+        };
+        self.pseudonyms.insert(key, pseudonym.clone());
+        pseudonym
+    }
+}
+
+/// Anonymize a tool's display text and structured output with one shared
+/// pseudonym table, or pass them through unchanged when not requested
+pub fn report<T: Serialize + DeserializeOwned>(
+    enabled: bool,
+    display: String,
+    output: T,
+) -> Result<(String, T), McpError> {
+    if !enabled {
+        return Ok((display, output));
+    }
+    let mut anonymizer = Anonymizer::new();
+    let display = anonymizer.text(&display);
+    Ok((display, anonymizer.output(output)?))
+}
+
+fn current_user() -> Option<String> {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+        .filter(|user| !user.is_empty())
+}
+
+/// The machine's hostname and its short form
+fn current_hostnames() -> Vec<String> {
+    #[cfg(unix)]
+    let hostname = nix::unistd::gethostname().ok().and_then(|name| name.into_string().ok());
+    #[cfg(not(unix))]
+    let hostname = std::env::var("COMPUTERNAME").ok();
+
+    let Some(hostname) = hostname.filter(|name| !name.is_empty()) else {
+        return Vec::new();
+    };
+    let short = hostname.split('.').next().unwrap_or_default().to_string();
+    if short != hostname && !short.is_empty() {
+        vec![hostname, short]
+    } else {
+        vec![hostname]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Anonymizer that knows nothing about the machine running the tests
+    fn anonymizer() -> Anonymizer {
+        Anonymizer {
+            home: None,
+            names: Vec::new(),
+            names_regex: None,
+            pseudonyms: HashMap::new(),
+            counters: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn pseudonyms_are_stable() {
+        let mut anonymizer = anonymizer();
+        let cases = [
+            ("mail alice@corp.com", "mail email1@example.com"),
+            ("mail bob@corp.com", "mail email2@example.com"),
+            ("again ALICE@corp.com", "again email1@example.com"),
+            ("cd /home/alice/src", "cd /home/user1/src"),
+            ("cd /Users/bob", "cd /Users/user2"),
+            (r"type C:\Users\alice\notes.txt", r"type C:\Users\user1\notes.txt"),
+            (r#"{"path":"C:\\Users\\bob\\x"}"#, r#"{"path":"C:\\Users\\user2\\x"}"#),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(anonymizer.text(input), expected, "{input}");
+        }
+        // A fresh anonymizer numbers from scratch, so reports cannot be correlated
+        assert_eq!(self::anonymizer().text("/home/bob"), "/home/user1");
+    }
+
+    #[test]
+    fn learned_names_replace_whole_words_only() {
+        let mut anonymizer = anonymizer();
+        anonymizer.learn(Kind::Host, "devbox");
+        anonymizer.text("/home/alice");
+
+        let cases = [
+            ("ssh alice@devbox", "ssh user1@host1"),
+            ("owner: Alice", "owner: user1"),
+            ("malice and alicex stay", "malice and alicex stay"),
+            ("alice_dev stays", "alice_dev stays"),
+            ("devbox.local", "host1.local"),
+            ("devboxes stay", "devboxes stay"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(anonymizer.text(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn generic_and_short_names_are_not_learned() {
+        let mut anonymizer = anonymizer();
+        assert_eq!(anonymizer.text("/home/root/x and /home/al/y"), "/home/user1/x and /home/user2/y");
+        assert_eq!(anonymizer.text("root and al stay"), "root and al stay");
+    }
+
+    #[test]
+    fn unusual_homes_keep_their_location() {
+        let mut anonymizer = anonymizer();
+        anonymizer.home = Some((Regex::new(r"/var/lib/alice\b").unwrap(), "/var/lib/".to_string()));
+        assert_eq!(anonymizer.text("cd /var/lib/alice/src"), "cd /var/lib/user1/src");
+
+        let mut anonymizer = self::anonymizer();
+        anonymizer.home = Some((Regex::new(r"D:\\Profiles\\alice\b").unwrap(), r"D:\Profiles\".to_string()));
+        assert_eq!(anonymizer.text(r"type D:\Profiles\alice\notes.txt"), r"type D:\Profiles\user1\notes.txt");
+    }
+
+    #[test]
+    fn json_keys_and_values() {
+        let mut anonymizer = anonymizer();
+        let mut value = serde_json::json!({ "/home/alice/a.rs": ["alice@corp.com", 3] });
+        anonymizer.value(&mut value);
+        assert_eq!(value, serde_json::json!({ "/home/user1/a.rs": ["email1@example.com", 3] }));
+    }
+}
@@ -0,0 +1,238 @@
+use chrono::Utc;
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use std::time::Duration;
+
+use crate::export_session::parse_bound;
+use crate::history::{resolve_connections, RecordedCall};
+use crate::redact::Redactor;
+use crate::render::SummaryStyle;
+use crate::schema::await_tool_call::{AwaitToolCallArgs, AwaitToolCallOutput, AWAIT_TOOL_CALL};
+use crate::stream::{CallFilter, CallWatcher, MAX_WAIT_SECS, POLL_INTERVAL};
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct AwaitToolCallTool;
+
+impl AwaitToolCallTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// What ended a wait before the timeout
+struct Outcome {
+    stopped_by: &'static str,
+    detail: String,
+    call: Option<RecordedCall>,
+    failure_rate: Option<f64>,
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for AwaitToolCallTool {
+    type Args = AwaitToolCallArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        AWAIT_TOOL_CALL
+    }
+
+    fn description() -> &'static str {
+        "Block until a tool call matching a filter appears in history, or until \
+         a condition holds, without busy polling inspect_tool_calls.\n\n\
+         By default the wait ends at the first call completing after it starts \
+         that matches tool_name, args_contains and failed_only (since also \
+         matches earlier calls). With failure_rate_above, it instead ends once \
+         more than that fraction of the last window calls failed; with \
+         idle_secs, once no call has completed for that long. Conditions are \
+         checked against existing history, so one that already holds returns \
+         immediately. Watch a sibling with connection_id or everything with \
+         all_connections (subject to the access policy).\n\n\
+         A timeout is not an error: the result reports satisfied=false and \
+         stopped_by=\"timeout\".\n\n\
+         Useful for:\n\
+         - Supervisor agents reacting to a sub-agent's next commit or test run\n\
+         - Intervening when a sub-agent starts failing repeatedly\n\
+         - Waiting for a sub-agent to go quiet before reviewing its work"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        if args.timeout_secs == 0 || args.timeout_secs > MAX_WAIT_SECS {
+            return Err(McpError::invalid_arguments(format!("timeout_secs must be between 1 and {MAX_WAIT_SECS}")));
+        }
+        if let Some(threshold) = args.failure_rate_above
+            && !(0.0..1.0).contains(&threshold)
+        {
+            return Err(McpError::invalid_arguments("failure_rate_above must be at least 0.0 and below 1.0"));
+        }
+        if args.window == 0 {
+            return Err(McpError::invalid_arguments("window must be greater than 0"));
+        }
+        if args.idle_secs == Some(0) {
+            return Err(McpError::invalid_arguments("idle_secs must be greater than 0"));
+        }
+        let since = parse_bound(args.since.as_deref(), "since")?;
+
+        // Other connections are subject to the access policy
+        let connections = resolve_connections(&ctx, args.connection_id.clone(), args.all_connections).await?;
+
+        let started = Utc::now();
+        let start = tokio::time::Instant::now();
+        let deadline = start + Duration::from_secs(args.timeout_secs);
+        let mut watcher = CallWatcher::new(connections, CallFilter { tool_name: args.tool_name.clone(), since });
+
+        let outcome = loop {
+            let (next, calls) = watcher.poll_blocking().await
+                .ok_or_else(|| McpError::Other(anyhow::anyhow!("Tool call watcher stopped unexpectedly")))?;
+            watcher = next;
+            let calls = calls?;
+
+            if let Some(outcome) = check_conditions(&args, watcher.history(), started) {
+                break Some(outcome);
+            }
+            if args.failure_rate_above.is_none() && args.idle_secs.is_none()
+                && let Some(call) = calls.into_iter().find(|call| considered(&args, call) && (!args.failed_only || call.is_failure()))
+            {
+                break Some(Outcome {
+                    stopped_by: "matched",
+                    detail: format!("{} completed at {}", call.record.tool_name, call.record.timestamp),
+                    call: Some(call),
+                    failure_rate: None,
+                });
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                break None;
+            }
+            tokio::select! {
+                () = tokio::time::sleep_until(deadline.min(now + POLL_INTERVAL)) => {}
+                () = ctx.cancellation_token().cancelled() => {
+                    break Some(Outcome {
+                        stopped_by: "cancelled",
+                        detail: "Wait cancelled by the client".to_string(),
+                        call: None,
+                        failure_rate: None,
+                    });
+                }
+            }
+        };
+        let waited_ms = start.elapsed().as_millis() as u64;
+
+        let satisfied = outcome.as_ref().is_some_and(|o| o.stopped_by != "cancelled");
+        let Outcome { stopped_by, detail, call, failure_rate } = outcome.unwrap_or_else(|| Outcome {
+            stopped_by: "timeout",
+            detail: format!("Nothing observed within {}s", args.timeout_secs),
+            call: None,
+            failure_rate: None,
+        });
+
+        let redactor = Redactor::load()?;
+        let call = call.map(|mut call| {
+            redactor.redact_record(&mut call.record);
+            call
+        });
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let summary = style.summary(
+            "Await Tool Call",
+            &format!(
+                "Satisfied: {} · Stopped by: {} · Waited: {:.1}s\n{}",
+                if satisfied { "yes" } else { "no" },
+                stopped_by,
+                waited_ms as f64 / 1000.0,
+                detail
+            ),
+        );
+
+        let output = AwaitToolCallOutput {
+            success: true,
+            satisfied,
+            stopped_by: stopped_by.to_string(),
+            detail,
+            waited_ms,
+            call_connection: call.as_ref().and_then(|call| call.connection_id.clone()),
+            call: call.map(|call| call.record),
+            failure_rate,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
+
+/// Whether a call passes the tool name and argument filters
+fn considered(args: &AwaitToolCallArgs, call: &RecordedCall) -> bool {
+    args.tool_name.as_ref().is_none_or(|name| &call.record.tool_name == name)
+        && args.args_contains.as_ref().is_none_or(|text| call.record.args_json.contains(text.as_str()))
+}
+
+/// Evaluate failure_rate_above and idle_secs against the current history
+fn check_conditions(
+    args: &AwaitToolCallArgs,
+    history: &[RecordedCall],
+    started: chrono::DateTime<Utc>,
+) -> Option<Outcome> {
+    let recent: Vec<&RecordedCall> = history.iter().filter(|call| considered(args, call)).collect();
+
+    if let Some(threshold) = args.failure_rate_above
+        && recent.len() >= args.window
+    {
+        let window = &recent[recent.len() - args.window..];
+        let failures = window.iter().filter(|call| call.is_failure()).count();
+        let rate = failures as f64 / args.window as f64;
+        if rate > threshold {
+            return Some(Outcome {
+                stopped_by: "failure_rate",
+                detail: format!(
+                    "{failures} of the last {} calls failed ({:.0}% > {:.0}%)",
+                    args.window,
+                    rate * 100.0,
+                    threshold * 100.0
+                ),
+                call: window.last().map(|call| (*call).clone()),
+                failure_rate: Some(rate),
+            });
+        }
+    }
+
+    if let Some(idle_secs) = args.idle_secs {
+        // Idle time counts from the wait's start if nothing newer was recorded
+        let last = recent.iter().rev().find_map(|call| call.time).unwrap_or(started);
+        let idle = (Utc::now() - last).num_seconds();
+        if idle >= idle_secs as i64 {
+            return Some(Outcome {
+                stopped_by: "idle",
+                detail: format!("No call completed for {idle}s"),
+                call: recent.last().map(|call| (*call).clone()),
+                failure_rate: None,
+            });
+        }
+    }
+
+    None
+}
@@ -0,0 +1,377 @@
+//! Usage budgets per session
+//!
+//! Budgets cap what one session of an agent may consume: calls per tool,
+//! total call duration and serialized output bytes. A connection's
+//! [`SessionUsage`] is computed from its history in kodegend, and servers
+//! consult [`check_connection`] (in-process) or the `check_budget` tool
+//! before executing a call.
+//!
+//! Budgets are read from `introspection-budget.json` in the user config
+//! directory. A repository's `${git_root}/.kodegen/` copy may only tighten
+//! them: every limit is the smaller of the two, so cloning a repository
+//! cannot lift the user's caps. All fields are optional:
+//!
+//! ```json
+//! {
+//!   "tool_calls": { "terminal": 200, "fs_delete_file": 20 },
+//!   "default_tool_calls": 500,
+//!   "max_duration_secs": 3600,
+//!   "max_output_bytes": 50000000
+//! }
+//! ```
+
+use kodegen_mcp_schema::McpError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::history::{fetch_history, RecordedCall};
+use crate::sessions::{self, DEFAULT_SESSION_TIMEOUT};
+
+/// Config file name; the user's file is the base, a repository's may only tighten it
+pub const BUDGET_CONFIG_FILE: &str = "introspection-budget.json";
+
+// ============================================================================
+// CONFIGURATION
+// ============================================================================
+
+/// Budget definitions from `introspection-budget.json`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// Maximum calls per session, by tool name
+    pub tool_calls: BTreeMap<String, u64>,
+    /// Maximum calls per session for tools not listed in `tool_calls`
+    pub default_tool_calls: Option<u64>,
+    /// Maximum total call duration per session, in seconds
+    pub max_duration_secs: Option<u64>,
+    /// Maximum serialized output bytes per session
+    pub max_output_bytes: Option<u64>,
+}
+
+impl BudgetConfig {
+    /// Load the user's budgets, tightened by the repository's
+    ///
+    /// No budgets apply without either file. A file that exists but cannot
+    /// be read or parsed is an error rather than silently lifting the user's caps.
+    pub fn load() -> Result<Self, McpError> {
+        let mut config = read_config(kodegen_config::KodegenConfig::user_config_dir())?.unwrap_or_default();
+        if let Some(local) = read_config(kodegen_config::KodegenConfig::local_config_dir())? {
+            config.tighten(local);
+        }
+        Ok(config)
+    }
+
+    /// Apply another set of budgets that may only lower limits
+    ///
+    /// Each limit becomes the smaller of the two; a limit set on one side only is kept.
+    pub fn tighten(&mut self, other: Self) {
+        for (tool_name, limit) in other.tool_calls {
+            let limit = self.tool_limit(&tool_name).map_or(limit, |current| current.min(limit));
+            self.tool_calls.insert(tool_name, limit);
+        }
+        self.default_tool_calls = min_limit(self.default_tool_calls, other.default_tool_calls);
+        self.max_duration_secs = min_limit(self.max_duration_secs, other.max_duration_secs);
+        self.max_output_bytes = min_limit(self.max_output_bytes, other.max_output_bytes);
+    }
+
+    /// Whether no budget is configured
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tool_calls.is_empty()
+            && self.default_tool_calls.is_none()
+            && self.max_duration_secs.is_none()
+            && self.max_output_bytes.is_none()
+    }
+
+    /// Call limit of a tool (None if uncapped)
+    #[must_use]
+    pub fn tool_limit(&self, tool_name: &str) -> Option<u64> {
+        self.tool_calls.get(tool_name).copied().or(self.default_tool_calls)
+    }
+
+    /// Every configured limit with its use in `usage`
+    ///
+    /// Per-tool limits are listed for tools named in `tool_calls` and, with
+    /// `default_tool_calls`, for every tool called this session.
+    #[must_use]
+    pub fn status(&self, usage: &SessionUsage) -> BudgetStatus {
+        let mut limits = Vec::new();
+
+        let mut tools: Vec<&String> = self.tool_calls.keys().collect();
+        if self.default_tool_calls.is_some() {
+            tools.extend(usage.tool_calls.keys().filter(|tool| !self.tool_calls.contains_key(*tool)));
+        }
+        tools.sort();
+        for tool_name in tools {
+            if let Some(limit) = self.tool_limit(tool_name) {
+                let used = usage.tool_calls.get(tool_name).copied().unwrap_or(0);
+                limits.push(BudgetLimit::new("calls", Some(tool_name.clone()), limit, used));
+            }
+        }
+        if let Some(secs) = self.max_duration_secs {
+            limits.push(BudgetLimit::new("duration_ms", None, secs.saturating_mul(1000), usage.duration_ms));
+        }
+        if let Some(bytes) = self.max_output_bytes {
+            limits.push(BudgetLimit::new("output_bytes", None, bytes, usage.output_bytes));
+        }
+
+        BudgetStatus {
+            session_id: (!usage.session_id.is_empty()).then(|| usage.session_id.clone()),
+            exhausted: limits.iter().any(|limit| limit.exhausted),
+            limits,
+        }
+    }
+
+    /// Check that one more call of `tool_name` fits the budget
+    pub fn check(&self, usage: &SessionUsage, tool_name: &str) -> Result<(), McpError> {
+        let mut limits = Vec::new();
+        if let Some(limit) = self.tool_limit(tool_name) {
+            let used = usage.tool_calls.get(tool_name).copied().unwrap_or(0);
+            limits.push(BudgetLimit::new("calls", Some(tool_name.to_string()), limit, used));
+        }
+        limits.extend(self.status(usage).limits.into_iter().filter(|limit| limit.tool_name.is_none()));
+
+        let exceeded: Vec<String> = limits
+            .iter()
+            .filter(|limit| limit.exhausted)
+            .map(BudgetLimit::describe)
+            .collect();
+        if exceeded.is_empty() {
+            return Ok(());
+        }
+        Err(McpError::PermissionDenied(format!(
+            "Session budget exhausted for '{tool_name}': {} (configured in {BUDGET_CONFIG_FILE})",
+            exceeded.join(", ")
+        )))
+    }
+}
+
+/// The lower of two optional limits (None: uncapped)
+fn min_limit(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Parsed `introspection-budget.json` in `dir`, or None when there is none
+fn read_config(dir: anyhow::Result<std::path::PathBuf>) -> Result<Option<BudgetConfig>, McpError> {
+    let Ok(dir) = dir else {
+        return Ok(None);
+    };
+    let path = dir.join(BUDGET_CONFIG_FILE);
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(McpError::Other(anyhow::anyhow!("Failed to read {}: {}", path.display(), e))),
+    };
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| McpError::Other(anyhow::anyhow!("Invalid budget config {}: {}", path.display(), e)))
+}
+
+// ============================================================================
+// USAGE AND STATUS
+// ============================================================================
+
+/// Resources consumed by the current session of a connection
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SessionUsage {
+    /// Session the usage belongs to
+    pub session_id: String,
+    /// Calls per tool
+    pub tool_calls: BTreeMap<String, u64>,
+    /// Total recorded call duration in milliseconds
+    pub duration_ms: u64,
+    /// Total recorded output bytes
+    pub output_bytes: u64,
+}
+
+/// Usage of the session of chronological `calls` still open at `now` (Unix timestamp)
+///
+/// Once the last session has timed out the next call opens a new one, so
+/// nothing counts against the budget.
+#[must_use]
+pub fn session_usage(calls: &[RecordedCall], now: i64, timeout: Duration) -> SessionUsage {
+    let Some((session, range)) = sessions::segment(calls, timeout).pop() else {
+        return SessionUsage::default();
+    };
+    if sessions::is_new_session(session.end, now, timeout) {
+        return SessionUsage::default();
+    }
+
+    let mut usage = SessionUsage {
+        session_id: session.id,
+        ..SessionUsage::default()
+    };
+    for call in &calls[range] {
+        *usage.tool_calls.entry(call.record.tool_name.clone()).or_insert(0) += 1;
+        usage.duration_ms += call.record.duration_ms.unwrap_or(0);
+        usage.output_bytes += call.record.output_json.len() as u64;
+    }
+    usage
+}
+
+/// Usage of a connection's current session, from its history in kodegend
+pub fn connection_usage(connection_id: &str) -> Result<SessionUsage, McpError> {
+    let history = fetch_history(connection_id)?;
+    Ok(session_usage(&history.calls, chrono::Utc::now().timestamp(), DEFAULT_SESSION_TIMEOUT))
+}
+
+/// Check that one more call of `tool_name` by `connection_id` fits the session budget
+///
+/// Servers call this, directly or through the `check_budget` tool, before
+/// executing a call. Usage counts the calls
+/// kodegend has recorded so far, so a call still in flight is not included.
+/// An exhausted budget is a `PermissionDenied` error naming the limits reached.
+pub fn check_connection(connection_id: &str, tool_name: &str) -> Result<(), McpError> {
+    let config = BudgetConfig::load()?;
+    if config.is_empty() {
+        return Ok(());
+    }
+    config.check(&connection_usage(connection_id)?, tool_name)
+}
+
+/// One budget limit and how much of it is used
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BudgetLimit {
+    /// Limited resource: "calls", "duration_ms" or "output_bytes"
+    pub resource: String,
+    /// Tool the limit applies to (calls only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// Maximum per session
+    pub limit: u64,
+    /// Used so far this session
+    pub used: u64,
+    /// Left this session
+    pub remaining: u64,
+    /// Whether the limit is reached
+    pub exhausted: bool,
+}
+
+impl BudgetLimit {
+    fn new(resource: &str, tool_name: Option<String>, limit: u64, used: u64) -> Self {
+        Self {
+            resource: resource.to_string(),
+            tool_name,
+            limit,
+            used,
+            remaining: limit.saturating_sub(used),
+            exhausted: used >= limit,
+        }
+    }
+
+    /// Whether the limit constrains calls of `tool_name`
+    #[must_use]
+    pub fn applies_to(&self, tool_name: &str) -> bool {
+        self.tool_name.as_deref().is_none_or(|name| name == tool_name)
+    }
+
+    /// Short description such as "terminal calls 200/200"
+    #[must_use]
+    pub fn describe(&self) -> String {
+        match &self.tool_name {
+            Some(tool_name) => format!("{tool_name} {} {}/{}", self.resource, self.used, self.limit),
+            None => format!("{} {}/{}", self.resource, self.used, self.limit),
+        }
+    }
+}
+
+/// Budget use of one session
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BudgetStatus {
+    /// Session the usage belongs to (None before the first call of a session)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Whether any limit is reached
+    pub exhausted: bool,
+    /// Every configured limit
+    pub limits: Vec<BudgetLimit>,
+}
+
+impl BudgetStatus {
+    /// Limits that constrain calls of `tool_name`
+    pub fn limits_for<'a>(&'a self, tool_name: &'a str) -> impl Iterator<Item = &'a BudgetLimit> {
+        self.limits.iter().filter(move |limit| limit.applies_to(tool_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kodegen_mcp_schema::introspection::ToolCallRecord;
+
+    fn call(tool_name: &str, timestamp: &str, duration_ms: u64, output_json: &str) -> RecordedCall {
+        RecordedCall {
+            server: "filesystem".to_string(),
+            time: crate::history::parse_timestamp(timestamp),
+            connection_id: None,
+            record: ToolCallRecord {
+                tool_name: tool_name.to_string(),
+                timestamp: timestamp.to_string(),
+                duration_ms: Some(duration_ms),
+                args_json: "{}".to_string(),
+                output_json: output_json.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn repository_budgets_only_tighten() {
+        let mut config = BudgetConfig {
+            tool_calls: BTreeMap::from([("terminal".to_string(), 200)]),
+            default_tool_calls: Some(500),
+            max_duration_secs: Some(3600),
+            max_output_bytes: None,
+        };
+        config.tighten(BudgetConfig {
+            tool_calls: BTreeMap::from([("terminal".to_string(), 1000), ("fs_read_file".to_string(), 900)]),
+            default_tool_calls: Some(1000),
+            max_duration_secs: Some(60),
+            max_output_bytes: Some(1000),
+        });
+
+        assert_eq!(config.tool_limit("terminal"), Some(200));
+        assert_eq!(config.tool_limit("fs_read_file"), Some(500));
+        assert_eq!(config.tool_limit("fs_write_file"), Some(500));
+        assert_eq!(config.max_duration_secs, Some(60));
+        assert_eq!(config.max_output_bytes, Some(1000));
+    }
+
+    #[test]
+    fn usage_covers_the_open_session_only() {
+        let calls = [
+            call("terminal", "2024-10-12T08:00:00Z", 5000, "{}"),
+            call("terminal", "2024-10-12T12:00:00Z", 1000, "12345"),
+            call("fs_read_file", "2024-10-12T12:01:00Z", 500, "{}"),
+        ];
+        let timeout = Duration::from_secs(1800);
+        let now = crate::history::parse_timestamp("2024-10-12T12:10:00Z").unwrap().timestamp();
+
+        let usage = session_usage(&calls, now, timeout);
+        assert_eq!(usage.session_id, "20241012T120000Z");
+        assert_eq!(usage.tool_calls, BTreeMap::from([("fs_read_file".to_string(), 1), ("terminal".to_string(), 1)]));
+        assert_eq!(usage.duration_ms, 1500);
+        assert_eq!(usage.output_bytes, 7);
+
+        // The session timed out: the next call starts from zero
+        assert_eq!(session_usage(&calls, now + 3600, timeout), SessionUsage::default());
+    }
+
+    #[test]
+    fn exhausted_limits_deny_calls() {
+        let config = BudgetConfig {
+            tool_calls: BTreeMap::from([("terminal".to_string(), 1)]),
+            ..BudgetConfig::default()
+        };
+        let usage = SessionUsage {
+            tool_calls: BTreeMap::from([("terminal".to_string(), 1)]),
+            ..SessionUsage::default()
+        };
+        assert!(matches!(config.check(&usage, "terminal"), Err(McpError::PermissionDenied(_))));
+        assert!(config.check(&usage, "fs_read_file").is_ok());
+    }
+}
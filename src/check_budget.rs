@@ -0,0 +1,101 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectUsageStatsPrompts;
+
+use crate::budget;
+use crate::history::{connection_id, resolve_connections};
+use crate::render::SummaryStyle;
+use crate::schema::check_budget::{CheckBudgetArgs, CheckBudgetOutput, CHECK_BUDGET};
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct CheckBudgetTool;
+
+impl CheckBudgetTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for CheckBudgetTool {
+    type Args = CheckBudgetArgs;
+    type Prompts = InspectUsageStatsPrompts;
+
+    fn name() -> &'static str {
+        CHECK_BUDGET
+    }
+
+    fn description() -> &'static str {
+        "Check whether one more call of tool_name fits the per-session budget \
+         in introspection-budget.json. Returns allowed: false with the limits \
+         reached once the session has exhausted its calls of the tool, its \
+         total call duration or its output bytes.\n\n\
+         Backend servers call this before executing a call, passing the \
+         connection_id of the agent making it; checking another connection \
+         than the calling one is subject to the access policy. Without a \
+         configured budget every call is allowed.\n\n\
+         Useful for:\n\
+         - Enforcing budgets in servers outside this crate\n\
+         - Asking before starting an expensive tool call"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        let requester = connection_id(&ctx)?.to_string();
+        let target = resolve_connections(&ctx, args.connection_id, false)
+            .await?
+            .pop()
+            .unwrap_or(requester);
+
+        // An exhausted budget is an answer, not a failure of the check
+        let reason = match budget::check_connection(&target, &args.tool_name) {
+            Ok(()) => None,
+            Err(McpError::PermissionDenied(reason)) => Some(reason),
+            Err(e) => return Err(e),
+        };
+
+        let style = SummaryStyle::resolve(args.style);
+        let summary = style.summary(
+            "Budget Check",
+            &format!(
+                "Tool: {} · Connection: {}\n{}",
+                args.tool_name,
+                target,
+                reason.as_deref().unwrap_or("Allowed")
+            ),
+        );
+
+        let output = CheckBudgetOutput {
+            success: true,
+            connection_id: target,
+            tool_name: args.tool_name,
+            allowed: reason.is_none(),
+            reason,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
@@ -0,0 +1,380 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectUsageStatsPrompts;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use crate::export_session::parse_bound;
+use crate::history::{fetch_connections_history, resolve_connections, RecordedCall};
+use crate::render::SummaryStyle;
+use crate::schema::compare_usage::{
+    CompareUsageArgs, CompareUsageOutput, SliceTotals, ToolDelta, UsageSlice, COMPARE_USAGE,
+};
+use crate::sessions;
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct CompareUsageTool;
+
+impl CompareUsageTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Per-tool counters of one slice
+#[derive(Default)]
+struct ToolUsage {
+    calls: u64,
+    failed: u64,
+    timed_calls: u64,
+    duration_ms: u64,
+}
+
+impl ToolUsage {
+    fn failure_rate(&self) -> f64 {
+        percentage(self.failed, self.calls)
+    }
+
+    fn avg_ms(&self) -> Option<f64> {
+        (self.timed_calls > 0).then(|| self.duration_ms as f64 / self.timed_calls as f64)
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for CompareUsageTool {
+    type Args = CompareUsageArgs;
+    type Prompts = InspectUsageStatsPrompts;
+
+    fn name() -> &'static str {
+        COMPARE_USAGE
+    }
+
+    fn description() -> &'static str {
+        "Compare tool usage between two slices of history, each a session id \
+         (see inspect_sessions) or a since/until window. Reports totals for \
+         both slices and, per tool, the call count delta, average latency \
+         delta and failure rate change, plus tools that are new in the \
+         candidate or no longer called.\n\n\
+         Compare the calling connection, another one with connection_id or \
+         the merged history of all connections with all_connections (subject \
+         to the access policy). Sessions are identified within each \
+         connection; a session id found in several connections must be \
+         narrowed down with connection_id.\n\n\
+         Useful for:\n\
+         - Checking whether a prompt change made an agent more efficient\n\
+         - Comparing today's run with yesterday's\n\
+         - Spotting tools an agent stopped or started using"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        if args.timeout_minutes == 0 {
+            return Err(McpError::invalid_arguments("timeout_minutes must be greater than 0"));
+        }
+
+        // Other connections' history is subject to the access policy
+        let connections = resolve_connections(&ctx, args.connection_id.clone(), args.all_connections).await?;
+        let calls = fetch_connections_history(&ctx, &connections, args.all_connections)?.calls;
+        let timeout = Duration::from_secs(args.timeout_minutes.saturating_mul(60));
+
+        let (baseline_label, baseline_calls) = select(&calls, &args.baseline, "baseline", timeout)?;
+        let (candidate_label, candidate_calls) = select(&calls, &args.candidate, "candidate", timeout)?;
+
+        let baseline_tools = tool_usage(&baseline_calls);
+        let candidate_tools = tool_usage(&candidate_calls);
+        let baseline = totals(baseline_label, &baseline_calls, &baseline_tools);
+        let candidate = totals(candidate_label, &candidate_calls, &candidate_tools);
+
+        let tools = tool_deltas(&baseline_tools, &candidate_tools);
+
+        let tools_with = |change: &str| -> Vec<String> {
+            tools.iter().filter(|tool| tool.change == change).map(|tool| tool.tool_name.clone()).collect()
+        };
+        let new_tools = tools_with("new");
+        let removed_tools = tools_with("removed");
+
+        let calls_delta = candidate.calls as i64 - baseline.calls as i64;
+        let failure_rate_delta = candidate.failure_rate - baseline.failure_rate;
+        let duration_delta_ms = candidate.total_duration_ms as i64 - baseline.total_duration_ms as i64;
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let summary = style.summary(
+            "Usage Comparison",
+            &format!(
+                "{} → {}\nCalls: {} → {} ({:+}) · Failure rate: {:.1}% → {:.1}% ({:+.1} pp) · Duration: {:+}ms\nNew tools: {} · Removed tools: {}",
+                baseline.label,
+                candidate.label,
+                baseline.calls,
+                candidate.calls,
+                calls_delta,
+                baseline.failure_rate,
+                candidate.failure_rate,
+                failure_rate_delta,
+                duration_delta_ms,
+                list_or_none(&new_tools),
+                list_or_none(&removed_tools)
+            ),
+        );
+
+        let output = CompareUsageOutput {
+            success: true,
+            baseline,
+            candidate,
+            calls_delta,
+            failure_rate_delta,
+            duration_delta_ms,
+            new_tools,
+            removed_tools,
+            tools,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
+
+/// Calls of one slice with a label describing it
+fn select(
+    calls: &[RecordedCall],
+    slice: &UsageSlice,
+    name: &str,
+    timeout: Duration,
+) -> Result<(String, Vec<RecordedCall>), McpError> {
+    if let Some(session_id) = slice.session_id.as_deref() {
+        if slice.since.is_some() || slice.until.is_some() {
+            return Err(McpError::invalid_arguments(format!("{name}: session_id and since/until are mutually exclusive")));
+        }
+
+        // Sessions belong to one connection, so merged histories are segmented per connection
+        let mut by_connection: BTreeMap<Option<&str>, Vec<RecordedCall>> = BTreeMap::new();
+        for call in calls {
+            by_connection.entry(call.connection_id.as_deref()).or_default().push(call.clone());
+        }
+        let mut found = by_connection.iter().filter_map(|(connection_id, calls)| {
+            sessions::segment(calls, timeout)
+                .into_iter()
+                .find(|(session, _)| session.id == session_id)
+                .map(|(_, range)| (*connection_id, calls[range].to_vec()))
+        });
+        let (connection_id, selected) = found
+            .next()
+            .ok_or_else(|| McpError::resource_not_found(format!("No session with id: {session_id}")))?;
+        if found.next().is_some() {
+            return Err(McpError::invalid_arguments(format!(
+                "{name}: session {session_id} exists in several connections; select one with connection_id"
+            )));
+        }
+        let label = match connection_id {
+            Some(connection_id) if by_connection.len() > 1 => format!("session {session_id} of {connection_id}"),
+            _ => format!("session {session_id}"),
+        };
+        return Ok((label, selected));
+    }
+
+    let since = parse_bound(slice.since.as_deref(), &format!("{name}.since"))?;
+    let until = parse_bound(slice.until.as_deref(), &format!("{name}.until"))?;
+    if since.is_none() && until.is_none() {
+        return Err(McpError::invalid_arguments(format!("{name} needs a session_id or a since/until window")));
+    }
+    let selected = calls
+        .iter()
+        .filter(|call| match call.time {
+            Some(time) => since.is_none_or(|since| time >= since) && until.is_none_or(|until| time <= until),
+            None => false,
+        })
+        .cloned()
+        .collect();
+    let label = format!(
+        "{}..{}",
+        slice.since.as_deref().unwrap_or("start"),
+        slice.until.as_deref().unwrap_or("now")
+    );
+    Ok((label, selected))
+}
+
+/// Per-tool deltas between two slices, largest call count change first
+fn tool_deltas(baseline_tools: &BTreeMap<String, ToolUsage>, candidate_tools: &BTreeMap<String, ToolUsage>) -> Vec<ToolDelta> {
+    let names: BTreeSet<&String> = baseline_tools.keys().chain(candidate_tools.keys()).collect();
+    let empty = ToolUsage::default();
+    let mut tools: Vec<ToolDelta> = names
+        .into_iter()
+        .map(|tool_name| {
+            let before = baseline_tools.get(tool_name).unwrap_or(&empty);
+            let after = candidate_tools.get(tool_name).unwrap_or(&empty);
+            let change = match (before.calls, after.calls) {
+                (0, _) => "new",
+                (_, 0) => "removed",
+                _ => "changed",
+            };
+            ToolDelta {
+                tool_name: tool_name.clone(),
+                change: change.to_string(),
+                baseline_calls: before.calls,
+                candidate_calls: after.calls,
+                calls_delta: after.calls as i64 - before.calls as i64,
+                baseline_failure_rate: before.failure_rate(),
+                candidate_failure_rate: after.failure_rate(),
+                failure_rate_delta: after.failure_rate() - before.failure_rate(),
+                baseline_avg_ms: before.avg_ms(),
+                candidate_avg_ms: after.avg_ms(),
+                avg_ms_delta: before.avg_ms().zip(after.avg_ms()).map(|(before, after)| after - before),
+            }
+        })
+        .collect();
+    tools.sort_by(|a, b| {
+        b.calls_delta.unsigned_abs().cmp(&a.calls_delta.unsigned_abs()).then_with(|| a.tool_name.cmp(&b.tool_name))
+    });
+    tools
+}
+
+fn tool_usage(calls: &[RecordedCall]) -> BTreeMap<String, ToolUsage> {
+    let mut tools: BTreeMap<String, ToolUsage> = BTreeMap::new();
+    for call in calls {
+        let usage = tools.entry(call.record.tool_name.clone()).or_default();
+        usage.calls += 1;
+        if call.is_failure() {
+            usage.failed += 1;
+        }
+        if let Some(duration_ms) = call.record.duration_ms {
+            usage.timed_calls += 1;
+            usage.duration_ms += duration_ms;
+        }
+    }
+    tools
+}
+
+fn totals(label: String, calls: &[RecordedCall], tools: &BTreeMap<String, ToolUsage>) -> SliceTotals {
+    let count = calls.len() as u64;
+    let failed_calls = tools.values().map(|usage| usage.failed).sum();
+    SliceTotals {
+        label,
+        calls: count,
+        failed_calls,
+        failure_rate: percentage(failed_calls, count),
+        tools_used: tools.len(),
+        total_duration_ms: tools.values().map(|usage| usage.duration_ms).sum(),
+        first_call: calls.first().map(|call| call.record.timestamp.clone()),
+        last_call: calls.last().map(|call| call.record.timestamp.clone()),
+    }
+}
+
+fn percentage(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64 * 100.0
+    }
+}
+
+fn list_or_none(tools: &[String]) -> String {
+    if tools.is_empty() {
+        "none".to_string()
+    } else {
+        tools.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kodegen_mcp_schema::introspection::ToolCallRecord;
+
+    fn call(connection_id: &str, tool_name: &str, timestamp: &str, duration_ms: u64, output_json: &str) -> RecordedCall {
+        RecordedCall {
+            server: "filesystem".to_string(),
+            time: crate::history::parse_timestamp(timestamp),
+            connection_id: Some(connection_id.to_string()),
+            record: ToolCallRecord {
+                tool_name: tool_name.to_string(),
+                timestamp: timestamp.to_string(),
+                duration_ms: Some(duration_ms),
+                args_json: "{}".to_string(),
+                output_json: output_json.to_string(),
+            },
+        }
+    }
+
+    fn session(session_id: &str) -> UsageSlice {
+        UsageSlice {
+            session_id: Some(session_id.to_string()),
+            ..UsageSlice::default()
+        }
+    }
+
+    #[test]
+    fn sessions_are_segmented_per_connection() {
+        let timeout = Duration::from_secs(1800);
+        // Interleaved connections: merged, conn-b would extend conn-a's session
+        let calls = [
+            call("conn-a", "fs_read_file", "2024-10-12T10:00:00Z", 10, "{}"),
+            call("conn-b", "terminal", "2024-10-12T10:20:00Z", 10, "{}"),
+            call("conn-b", "terminal", "2024-10-12T10:40:00Z", 10, "{}"),
+            call("conn-a", "fs_read_file", "2024-10-12T11:00:00Z", 10, "{}"),
+        ];
+
+        let (label, selected) = select(&calls, &session("20241012T110000Z"), "baseline", timeout).unwrap();
+        assert_eq!(label, "session 20241012T110000Z of conn-a");
+        assert_eq!(selected.len(), 1);
+
+        let (_, selected) = select(&calls, &session("20241012T102000Z"), "baseline", timeout).unwrap();
+        assert_eq!(selected.iter().map(|call| call.record.tool_name.as_str()).collect::<Vec<_>>(), ["terminal", "terminal"]);
+
+        // The same id in two connections is ambiguous
+        let twins = [
+            call("conn-a", "fs_read_file", "2024-10-12T10:00:00Z", 10, "{}"),
+            call("conn-b", "terminal", "2024-10-12T10:00:00Z", 10, "{}"),
+        ];
+        assert!(matches!(
+            select(&twins, &session("20241012T100000Z"), "baseline", timeout),
+            Err(McpError::InvalidArguments(_))
+        ));
+    }
+
+    #[test]
+    fn deltas_classify_tools() {
+        let baseline = [
+            call("conn-a", "fs_read_file", "2024-10-12T10:00:00Z", 100, "{}"),
+            call("conn-a", "fs_read_file", "2024-10-12T10:01:00Z", 300, r#"{"error":"not found"}"#),
+            call("conn-a", "terminal", "2024-10-12T10:02:00Z", 50, "{}"),
+        ];
+        let candidate = [
+            call("conn-a", "fs_read_file", "2024-10-13T10:00:00Z", 50, "{}"),
+            call("conn-a", "fs_search", "2024-10-13T10:01:00Z", 20, "{}"),
+        ];
+
+        let tools = tool_deltas(&tool_usage(&baseline), &tool_usage(&candidate));
+        let changes: Vec<(&str, &str)> = tools.iter().map(|tool| (tool.tool_name.as_str(), tool.change.as_str())).collect();
+        assert_eq!(changes, [("fs_read_file", "changed"), ("fs_search", "new"), ("terminal", "removed")]);
+
+        let read = &tools[0];
+        assert_eq!(read.calls_delta, -1);
+        assert_eq!(read.baseline_avg_ms, Some(200.0));
+        assert_eq!(read.avg_ms_delta, Some(-150.0));
+        assert_eq!(read.failure_rate_delta, -50.0);
+        assert_eq!(tools[1].avg_ms_delta, None);
+        assert_eq!(tools[2].avg_ms_delta, None);
+    }
+}
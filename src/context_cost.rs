@@ -0,0 +1,133 @@
+//! Context-cost accounting: how much of an agent's context each tool consumes
+//!
+//! Every call's argument and output sizes are measured in bytes and converted
+//! to an approximate token count by a [`TokenEstimator`]. Estimators are
+//! heuristics, not real tokenizers; implement the trait to plug in another one.
+
+use kodegen_mcp_schema::introspection::ToolCallRecord;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Approximates how many tokens a piece of text occupies in a model's context
+pub trait TokenEstimator: Send + Sync {
+    /// Short identifier reported alongside estimates
+    fn name(&self) -> &'static str;
+
+    /// Estimated token count for `text`
+    fn estimate(&self, text: &str) -> u64;
+}
+
+/// Fixed characters-per-token ratio (~4 for English text and code)
+#[derive(Debug, Clone, Copy)]
+pub struct CharRatioEstimator {
+    pub chars_per_token: f64,
+}
+
+impl Default for CharRatioEstimator {
+    fn default() -> Self {
+        Self { chars_per_token: 4.0 }
+    }
+}
+
+impl TokenEstimator for CharRatioEstimator {
+    fn name(&self) -> &'static str {
+        "chars"
+    }
+
+    fn estimate(&self, text: &str) -> u64 {
+        (text.chars().count() as f64 / self.chars_per_token).ceil() as u64
+    }
+}
+
+/// Words and punctuation, closer to BPE tokenizers on prose and JSON
+///
+/// Each whitespace-separated word costs one token per 4 alphanumeric
+/// characters (at least one), and every punctuation character costs one more.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WordEstimator;
+
+impl TokenEstimator for WordEstimator {
+    fn name(&self) -> &'static str {
+        "words"
+    }
+
+    fn estimate(&self, text: &str) -> u64 {
+        text.split_whitespace()
+            .map(|word| {
+                let punctuation = word.chars().filter(|c| c.is_ascii_punctuation()).count();
+                let letters = word.chars().count() - punctuation;
+                (letters.div_ceil(4) + punctuation).max(1) as u64
+            })
+            .sum()
+    }
+}
+
+/// Built-in token heuristics, selectable by name in tool arguments
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenHeuristic {
+    /// ~4 characters per token
+    #[default]
+    Chars,
+    /// Word and punctuation based estimate
+    Words,
+}
+
+impl TokenHeuristic {
+    #[must_use]
+    pub fn estimator(self) -> Arc<dyn TokenEstimator> {
+        match self {
+            Self::Chars => Arc::new(CharRatioEstimator::default()),
+            Self::Words => Arc::new(WordEstimator),
+        }
+    }
+}
+
+/// Accumulated context cost of one tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ToolCost {
+    /// Calls measured
+    pub calls: u64,
+    /// Total size of serialized arguments
+    pub args_bytes: u64,
+    /// Total size of serialized outputs
+    pub output_bytes: u64,
+    /// Estimated tokens for arguments and outputs combined
+    pub estimated_tokens: u64,
+}
+
+impl ToolCost {
+    /// Cost of a single call
+    #[must_use]
+    pub fn of_call(args_json: &str, output_json: &str, estimator: &dyn TokenEstimator) -> Self {
+        Self {
+            calls: 1,
+            args_bytes: args_json.len() as u64,
+            output_bytes: output_json.len() as u64,
+            estimated_tokens: estimator.estimate(args_json) + estimator.estimate(output_json),
+        }
+    }
+
+    pub fn add(&mut self, other: &Self) {
+        self.calls += other.calls;
+        self.args_bytes += other.args_bytes;
+        self.output_bytes += other.output_bytes;
+        self.estimated_tokens += other.estimated_tokens;
+    }
+}
+
+/// Context cost per tool over a set of recorded calls
+#[must_use]
+pub fn tool_costs<'a>(
+    records: impl IntoIterator<Item = &'a ToolCallRecord>,
+    estimator: &dyn TokenEstimator,
+) -> HashMap<String, ToolCost> {
+    let mut costs = HashMap::<String, ToolCost>::new();
+    for record in records {
+        let cost = ToolCost::of_call(&record.args_json, &record.output_json, estimator);
+        costs.entry(record.tool_name.clone()).or_default().add(&cost);
+    }
+    costs
+}
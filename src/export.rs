@@ -0,0 +1,216 @@
+//! Export formats for tool call history
+//!
+//! Renderers turn recorded calls into formats external tools can open.
+//! The recorded timestamp marks the end of a call, so start times are
+//! derived by subtracting the call's duration.
+
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use crate::history::RecordedCall;
+
+/// Chrome Trace Event JSON (chrome://tracing, Perfetto)
+///
+/// Each server becomes a process; calls that overlap in time are spread over
+/// numbered lanes (threads) so concurrency shows up as stacked tracks and
+/// idle time as gaps. Failed calls are coloured red. With `by_connection`
+/// every connection gets its own set of server processes.
+#[must_use]
+pub fn chrome_trace(calls: &[RecordedCall], by_connection: bool) -> Value {
+    // Calls per server, ordered by start time (microseconds since the epoch)
+    let mut servers: BTreeMap<String, Vec<(i64, i64, &RecordedCall)>> = BTreeMap::new();
+    for call in calls {
+        let Some(end) = call.time.map(|time| time.timestamp_micros()) else {
+            continue;
+        };
+        let dur = i64::try_from(call.record.duration_ms.unwrap_or(0).saturating_mul(1000)).unwrap_or(i64::MAX);
+        let process = match (&call.connection_id, by_connection) {
+            (Some(connection_id), true) => format!("{connection_id}/{}", call.server),
+            _ => call.server.clone(),
+        };
+        servers.entry(process).or_default().push((end.saturating_sub(dur), dur, call));
+    }
+
+    let mut events = Vec::new();
+
+    for (pid, (server, mut server_calls)) in servers.into_iter().enumerate() {
+        let pid = pid + 1;
+        server_calls.sort_by_key(|(start, _, _)| *start);
+
+        events.push(json!({
+            "name": "process_name",
+            "ph": "M",
+            "pid": pid,
+            "args": { "name": server },
+        }));
+
+        // End time of the last call placed on each lane
+        let mut lanes: Vec<i64> = Vec::new();
+
+        for (start, dur, call) in server_calls {
+            let lane = match lanes.iter().position(|end| *end <= start) {
+                Some(lane) => lane,
+                None => {
+                    lanes.push(start);
+                    events.push(json!({
+                        "name": "thread_name",
+                        "ph": "M",
+                        "pid": pid,
+                        "tid": lanes.len(),
+                        "args": { "name": format!("lane {}", lanes.len()) },
+                    }));
+                    lanes.len() - 1
+                }
+            };
+            lanes[lane] = start + dur;
+
+            let failed = call.is_failure();
+            let mut event = json!({
+                "name": call.record.tool_name,
+                "cat": call.server,
+                "ph": "X",
+                "ts": start,
+                "dur": dur,
+                "pid": pid,
+                "tid": lane + 1,
+                "args": {
+                    "timestamp": call.record.timestamp,
+                    "duration_ms": call.record.duration_ms,
+                    "failed": failed,
+                    "args_bytes": call.record.args_json.len(),
+                    "output_bytes": call.record.output_json.len(),
+                },
+            });
+            if failed {
+                event["cname"] = json!("terrible");
+            }
+            events.push(event);
+        }
+    }
+
+    json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+    })
+}
+
+/// One JSON object per call, in the given order; `with_connection` adds each call's connection
+#[must_use]
+pub fn tool_calls_jsonl(calls: &[RecordedCall], with_connection: bool) -> String {
+    calls
+        .iter()
+        .map(|call| call_json(call, with_connection).to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Flat JSON object describing one call (JSONL lines, streamed events)
+#[must_use]
+pub fn call_json(call: &RecordedCall, with_connection: bool) -> Value {
+    let mut object = json!({
+        "timestamp": call.record.timestamp,
+        "server": call.server,
+        "tool_name": call.record.tool_name,
+        "duration_ms": call.record.duration_ms,
+        "failed": call.is_failure(),
+        "args_json": call.record.args_json,
+        "output_json": call.record.output_json,
+    });
+    if with_connection {
+        object["connection_id"] = json!(call.connection_id);
+    }
+    object
+}
+
+/// Tabular view of calls for CSV and Markdown; `max_cell` caps args/output cells (0: no cap)
+///
+/// `with_connection` adds a leading `connection_id` column.
+#[must_use]
+pub fn tool_calls_table(calls: &[RecordedCall], max_cell: usize, with_connection: bool) -> Table {
+    let columns = ["connection_id", "timestamp", "server", "tool_name", "duration_ms", "status", "args", "output"];
+    let mut table = Table::new(&columns[usize::from(!with_connection)..]);
+    for call in calls {
+        let mut row = vec![
+            call.record.timestamp.clone(),
+            call.server.clone(),
+            call.record.tool_name.clone(),
+            call.record.duration_ms.map(|ms| ms.to_string()).unwrap_or_default(),
+            if call.is_failure() { "failed" } else { "ok" }.to_string(),
+            truncate(&call.record.args_json, max_cell),
+            truncate(&call.record.output_json, max_cell),
+        ];
+        if with_connection {
+            row.insert(0, call.connection_id.clone().unwrap_or_default());
+        }
+        table.push(row);
+    }
+    table
+}
+
+/// Simple header + rows table rendered as CSV or Markdown
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    #[must_use]
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| (*h).to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// RFC 4180 CSV with a header line
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        std::iter::once(&self.headers)
+            .chain(&self.rows)
+            .map(|row| row.iter().map(|cell| csv_cell(cell)).collect::<Vec<_>>().join(","))
+            .collect::<Vec<_>>()
+            .join("\r\n")
+    }
+
+    /// GitHub-flavoured Markdown table
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let line = |row: &[String]| {
+            let cells: Vec<String> = row.iter().map(|cell| markdown_cell(cell)).collect();
+            format!("| {} |", cells.join(" | "))
+        };
+
+        let mut lines = vec![
+            line(&self.headers),
+            format!("|{}", " --- |".repeat(self.headers.len())),
+        ];
+        lines.extend(self.rows.iter().map(|row| line(row)));
+        lines.join("\n")
+    }
+}
+
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+fn markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if max_chars == 0 || text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars).collect();
+    truncated.push('…');
+    truncated
+}
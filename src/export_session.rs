@@ -0,0 +1,168 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use std::time::Duration;
+
+use crate::anonymize;
+use crate::history::{connection_id, fetch_history, parse_timestamp, RecordedCall};
+use crate::redact::Redactor;
+use crate::render::SummaryStyle;
+use crate::schema::export_session::{
+    ExportSessionArgs, ExportSessionOutput, ReplayScript, ReplayStep, EXPORT_SESSION,
+    REPLAY_SCRIPT_VERSION,
+};
+use crate::sessions;
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct ExportSessionTool;
+
+impl ExportSessionTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for ExportSessionTool {
+    type Args = ExportSessionArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        EXPORT_SESSION
+    }
+
+    fn description() -> &'static str {
+        "Export a slice of tool history as a replay script: an ordered list of \
+         steps with tool name and arguments, plus the captured output and failure \
+         status of the original call as expectations.\n\n\
+         Select the slice with session_id (see inspect_sessions) and/or since/until \
+         timestamps; without either the whole history is exported. Set \
+         include_outputs: false for a smaller script without expectations.\n\n\
+         Useful for:\n\
+         - Reproducing an agent run for debugging by feeding the steps back through an MCP client\n\
+         - Attaching a minimal reproduction to a bug report\n\
+         - Checking a run against its expectations with replay_tool_calls"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        if args.timeout_minutes == 0 {
+            return Err(McpError::invalid_arguments("timeout_minutes must be greater than 0"));
+        }
+
+        let since = parse_bound(args.since.as_deref(), "since")?;
+        let until = parse_bound(args.until.as_deref(), "until")?;
+
+        let history = fetch_history(connection_id(&ctx)?)?;
+
+        let mut calls: &[RecordedCall] = &history.calls;
+        if let Some(session_id) = args.session_id.as_deref() {
+            let timeout = Duration::from_secs(args.timeout_minutes.saturating_mul(60));
+            let (_, range) = sessions::segment(calls, timeout)
+                .into_iter()
+                .find(|(session, _)| session.id == session_id)
+                .ok_or_else(|| McpError::resource_not_found(format!("No session with id: {session_id}")))?;
+            calls = &calls[range];
+        }
+
+        let mut steps: Vec<ReplayStep> = calls
+            .iter()
+            .filter(|call| match (since, call.time) {
+                (Some(since), Some(time)) => time >= since,
+                _ => true,
+            })
+            .filter(|call| match (until, call.time) {
+                (Some(until), Some(time)) => time <= until,
+                _ => true,
+            })
+            .map(|call| replay_step(call, args.include_outputs))
+            .collect();
+
+        // Mask secrets before the script leaves the tool
+        let redactor = Redactor::load()?;
+        let redactions: usize = steps
+            .iter_mut()
+            .map(|step| {
+                redactor.redact_value(&mut step.args)
+                    + step.expected_output.as_mut().map_or(0, |output| redactor.redact_value(output))
+            })
+            .sum();
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let failures = steps.iter().filter(|step| step.expected_failure).count();
+        let summary = style.summary(
+            "Session Export",
+            &format!(
+                "Steps: {} · Expected failures: {} · Redacted: {} · Session: {}",
+                steps.len(),
+                failures,
+                redactions,
+                args.session_id.as_deref().unwrap_or("all")
+            ),
+        );
+
+        let output = ExportSessionOutput {
+            success: true,
+            count: steps.len(),
+            redactions,
+            script: ReplayScript {
+                version: REPLAY_SCRIPT_VERSION,
+                exported_at: chrono::Utc::now().to_rfc3339(),
+                session_id: args.session_id,
+                steps,
+            },
+        };
+
+        // Pseudonymize machine details for shareable reports
+        let (summary, output) = anonymize::report(args.anonymize, summary, output)?;
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
+
+/// Replay step for a recorded call, optionally with its output as expectation
+pub(crate) fn replay_step(call: &RecordedCall, include_output: bool) -> ReplayStep {
+    ReplayStep {
+        tool_name: call.record.tool_name.clone(),
+        args: call.args(),
+        server: call.server.clone(),
+        timestamp: call.record.timestamp.clone(),
+        duration_ms: call.record.duration_ms,
+        expected_output: include_output.then(|| {
+            serde_json::from_str(&call.record.output_json)
+                .unwrap_or_else(|_| serde_json::Value::String(call.record.output_json.clone()))
+        }),
+        expected_failure: call.is_failure(),
+    }
+}
+
+pub(crate) fn parse_bound(value: Option<&str>, name: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>, McpError> {
+    value
+        .map(|value| parse_timestamp(value)
+            .ok_or_else(|| McpError::invalid_arguments(format!("Invalid {name} timestamp: {value}"))))
+        .transpose()
+}
@@ -0,0 +1,303 @@
+//! Shared access to aggregated tool call history
+//!
+//! Every history-based tool queries kodegend the same way: resolve the
+//! connection, fetch per-server history over IPC and flatten it into a
+//! chronological list. This module keeps that in one place.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use kodegen_mcp_schema::{McpError, ToolExecutionContext};
+use kodegen_mcp_schema::introspection::ToolCallRecord;
+use kodegend_client_ipc::get_tool_history;
+
+use crate::access::AccessPolicy;
+use crate::usage_tracker::UsageTracker;
+
+/// A tool call together with the server that executed it
+#[derive(Debug, Clone)]
+pub struct RecordedCall {
+    /// Category of the backend server that executed the call
+    pub server: String,
+    /// The recorded call
+    pub record: ToolCallRecord,
+    /// Parsed call timestamp (None if the record's timestamp is malformed)
+    pub time: Option<DateTime<Utc>>,
+    /// Connection that made the call (None for history read from disk)
+    pub connection_id: Option<String>,
+}
+
+impl RecordedCall {
+    /// Whether the recorded output indicates the call failed
+    #[must_use]
+    pub fn is_failure(&self) -> bool {
+        is_failure_output(&self.record.output_json)
+    }
+
+    /// Parsed call arguments (Null if the recorded JSON is malformed)
+    #[must_use]
+    pub fn args(&self) -> serde_json::Value {
+        serde_json::from_str(&self.record.args_json).unwrap_or_default()
+    }
+}
+
+/// Tool call history for one connection (or several merged), oldest call first
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionHistory {
+    /// All calls across all available servers, sorted oldest first
+    pub calls: Vec<RecordedCall>,
+    /// Total number of calls held in memory by the backend servers
+    pub total_calls: usize,
+    /// HTTP port of each available backend server, by category
+    pub ports: HashMap<String, u16>,
+}
+
+/// Resolve the calling connection from the execution context
+pub fn connection_id(ctx: &ToolExecutionContext) -> Result<&str, McpError> {
+    ctx.connection_id()
+        .ok_or_else(|| McpError::Other(anyhow::anyhow!("No connection ID available - tool history requires connection context")))
+}
+
+/// Query the history of `target` (default: the calling connection) after
+/// checking the access policy
+pub fn fetch_authorized_history(ctx: &ToolExecutionContext, target: Option<&str>) -> Result<ConnectionHistory, McpError> {
+    let requester = connection_id(ctx)?;
+    let target = target.unwrap_or(requester);
+    AccessPolicy::load()?.authorize_connection(requester, target)?;
+    fetch_history(target)
+}
+
+/// Query the merged history of several connections after checking the
+/// access policy, in chronological order
+///
+/// With `all_connections`, connections without history (e.g. ones that have
+/// since disconnected) are skipped; the calling connection's errors are still
+/// reported. An explicitly requested connection always reports its errors.
+pub fn fetch_connections_history(
+    ctx: &ToolExecutionContext,
+    targets: &[String],
+    all_connections: bool,
+) -> Result<ConnectionHistory, McpError> {
+    let requester = connection_id(ctx)?;
+    let policy = AccessPolicy::load()?;
+
+    let mut merged = ConnectionHistory::default();
+    for target in targets {
+        policy.authorize_connection(requester, target)?;
+        let history = match fetch_history(target) {
+            Ok(history) => history,
+            Err(e) if !all_connections || target == requester => return Err(e),
+            Err(e) => {
+                log::debug!("Skipping history of connection {target}: {e}");
+                continue;
+            }
+        };
+        merged.calls.extend(history.calls);
+        merged.total_calls += history.total_calls;
+        merged.ports.extend(history.ports);
+    }
+
+    merged.calls.sort_by(|a, b| a.record.timestamp.cmp(&b.record.timestamp));
+    Ok(merged)
+}
+
+/// Connections selected by the `connection_id` / `all_connections` arguments
+///
+/// Defaults to the calling connection; other connections are checked
+/// against the access policy.
+pub async fn resolve_connections(
+    ctx: &ToolExecutionContext,
+    target: Option<String>,
+    all_connections: bool,
+) -> Result<Vec<String>, McpError> {
+    let requester = connection_id(ctx)?;
+    match (target, all_connections) {
+        (Some(_), true) => Err(McpError::invalid_arguments("connection_id and all_connections are mutually exclusive")),
+        (Some(target), false) => {
+            AccessPolicy::load()?.authorize_connection(requester, &target)?;
+            Ok(vec![target])
+        }
+        (None, true) => known_connections(ctx).await,
+        (None, false) => Ok(vec![requester.to_string()]),
+    }
+}
+
+/// Connection ids a requester may list for an `all_connections` view
+///
+/// kodegend has no connection listing, so ids are taken from the
+/// per-connection entries of the stats files the policy allows reading,
+/// plus the requester itself.
+pub async fn known_connections(ctx: &ToolExecutionContext) -> Result<Vec<String>, McpError> {
+    let requester = connection_id(ctx)?;
+    let policy = AccessPolicy::load()?;
+
+    let mut connections = list_connections(policy).await?;
+    if let Err(index) = connections.binary_search_by(|id| id.as_str().cmp(requester)) {
+        connections.insert(index, requester.to_string());
+    }
+    Ok(connections)
+}
+
+/// Connection ids recorded in the stats files `policy` allows reading, sorted
+pub async fn list_connections(policy: AccessPolicy) -> Result<Vec<String>, McpError> {
+    policy.authorize_cross_connection("all_connections")?;
+
+    let mut connections: Vec<String> = policy
+        .visible_instances(UsageTracker::load_all_instances().await)
+        .into_iter()
+        .flat_map(|instance| instance.connections.into_keys())
+        .collect();
+    connections.sort();
+    connections.dedup();
+    Ok(connections)
+}
+
+/// Query kodegend for a connection's tool history across all backend servers
+pub fn fetch_history(connection_id: &str) -> Result<ConnectionHistory, McpError> {
+    let history = get_tool_history(connection_id)
+        .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to query tool history from kodegend: {}", e)))?;
+
+    let ports = history.servers
+        .iter()
+        .filter(|server| server.available)
+        .map(|server| (server.category.clone(), server.port))
+        .collect();
+
+    // Flatten all calls from all servers and map IPC types to schema types
+    let mut calls: Vec<RecordedCall> = history.servers
+        .into_iter()
+        .flat_map(|server| {
+            let category = server.category;
+            server.calls.into_iter().map(move |ipc_call| RecordedCall {
+                server: category.clone(),
+                time: parse_timestamp(&ipc_call.timestamp),
+                connection_id: Some(connection_id.to_string()),
+                record: ToolCallRecord {
+                    tool_name: ipc_call.tool_name,
+                    timestamp: ipc_call.timestamp,
+                    duration_ms: ipc_call.duration_ms,
+                    args_json: ipc_call.args_json,
+                    output_json: ipc_call.output_json,
+                },
+            })
+        })
+        .collect();
+
+    calls.sort_by(|a, b| a.record.timestamp.cmp(&b.record.timestamp));
+
+    Ok(ConnectionHistory {
+        calls,
+        total_calls: history.total_calls,
+        ports,
+    })
+}
+
+/// Parse an ISO 8601 timestamp into UTC
+#[must_use]
+pub fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Human-readable error message of a failed call's output, if present
+#[must_use]
+pub fn failure_message(output_json: &str) -> Option<String> {
+    let output: serde_json::Value = serde_json::from_str(output_json).ok()?;
+
+    ["error", "message", "stderr"]
+        .iter()
+        .filter_map(|key| output.get(key))
+        .find_map(|value| match value {
+            serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+            serde_json::Value::Object(obj) => obj.get("message").and_then(|m| m.as_str()).map(str::to_string),
+            _ => None,
+        })
+}
+
+/// Whether a recorded tool output indicates failure
+///
+/// Tools report failure either as `"success": false`, an `"is_error": true`
+/// flag (MCP `CallToolResult`) or a top-level `"error"` field.
+#[must_use]
+pub fn is_failure_output(output_json: &str) -> bool {
+    let Ok(serde_json::Value::Object(output)) = serde_json::from_str(output_json) else {
+        return false;
+    };
+
+    output.get("success").and_then(serde_json::Value::as_bool) == Some(false)
+        || output.get("is_error").and_then(serde_json::Value::as_bool) == Some(true)
+        || output.get("isError").and_then(serde_json::Value::as_bool) == Some(true)
+        || output.get("error").is_some_and(|e| !e.is_null())
+}
+
+/// Tool history persisted by one server instance in `tool-history_{instance_id}.jsonl`
+#[derive(Debug, Clone)]
+pub struct InstanceHistory {
+    /// Instance id taken from the file name
+    pub instance_id: String,
+    /// Persisted calls in file (oldest first) order
+    pub calls: Vec<ToolCallRecord>,
+}
+
+/// Instance id of a `tool-history_{instance_id}.jsonl` file name
+pub(crate) fn history_instance_id(file_name: &str) -> Option<&str> {
+    file_name.strip_prefix("tool-history_")?.strip_suffix(".jsonl")
+}
+
+/// Load every instance's persisted tool history from the kodegen log directory
+///
+/// Unlike [`fetch_history`] this does not need kodegend and covers all
+/// connections, but only holds calls the servers have already flushed to disk.
+pub async fn load_disk_history() -> Vec<InstanceHistory> {
+    let mut instances = Vec::new();
+
+    let Ok(log_dir) = kodegen_config::KodegenConfig::log_dir() else {
+        return instances;
+    };
+    let Ok(mut entries) = tokio::fs::read_dir(log_dir).await else {
+        return instances;
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let file_name = entry.file_name();
+        let Some(instance_id) = file_name.to_str().and_then(history_instance_id) else {
+            continue;
+        };
+
+        match tokio::fs::read_to_string(entry.path()).await {
+            Ok(contents) => instances.push(InstanceHistory {
+                instance_id: instance_id.to_string(),
+                calls: contents
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect(),
+            }),
+            Err(e) => log::warn!("Failed to read tool history {}: {}", entry.path().display(), e),
+        }
+    }
+
+    instances.sort_by(|a, b| a.instance_id.cmp(&b.instance_id));
+    instances
+}
+
+impl InstanceHistory {
+    /// Server category, the instance id's leading `{category}-` component
+    #[must_use]
+    pub fn server(&self) -> &str {
+        self.instance_id.split('-').next().unwrap_or(&self.instance_id)
+    }
+
+    /// Persisted calls as [`RecordedCall`]s attributed to this instance's server
+    #[must_use]
+    pub fn recorded_calls(&self) -> Vec<RecordedCall> {
+        self.calls
+            .iter()
+            .map(|record| RecordedCall {
+                server: self.server().to_string(),
+                time: parse_timestamp(&record.timestamp),
+                record: record.clone(),
+                connection_id: None,
+            })
+            .collect()
+    }
+}
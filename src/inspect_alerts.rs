@@ -0,0 +1,115 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+
+use crate::alerts::{self, AlertConfig};
+use crate::export_session::parse_bound;
+use crate::history::resolve_connections;
+use crate::render::SummaryStyle;
+use crate::schema::inspect_alerts::{AlertEntry, InspectAlertsArgs, InspectAlertsOutput, INSPECT_ALERTS};
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct InspectAlertsTool;
+
+impl InspectAlertsTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for InspectAlertsTool {
+    type Args = InspectAlertsArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        INSPECT_ALERTS
+    }
+
+    fn description() -> &'static str {
+        "List alerts raised by the alert rules in introspection-alerts.json. \
+         The server evaluates the rules over a sliding window of each \
+         connection's calls (failure rate, latency percentile, call count) and \
+         records an alert when a condition starts to hold, resolving it once it stops.\n\n\
+         Returns the calling connection's alerts by default; set connection_id \
+         for a sibling connection or all_connections for every known connection, \
+         both subject to the access policy. Filter by rule, since or active_only.\n\n\
+         Useful for:\n\
+         - Checking whether any agent is failing or stuck in slow calls\n\
+         - Reviewing what went wrong during an unattended run\n\
+         - Verifying new alert rules"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        let since = parse_bound(args.since.as_deref(), "since")?.map(|since| since.timestamp());
+        let rules: Vec<String> = AlertConfig::load()?.rules.into_iter().map(|rule| rule.name).collect();
+
+        // Other connections' alerts are subject to the access policy
+        let connections = resolve_connections(&ctx, args.connection_id, args.all_connections).await?;
+        let mut persisted = alerts::load_alerts().await;
+
+        let mut alerts: Vec<AlertEntry> = connections
+            .into_iter()
+            .flat_map(|connection_id| {
+                let fired = persisted.remove(&connection_id).unwrap_or_default();
+                fired.into_iter().map(move |alert| AlertEntry { connection_id: connection_id.clone(), alert })
+            })
+            .filter(|entry| args.rule.as_ref().is_none_or(|rule| &entry.alert.rule == rule))
+            .filter(|entry| !args.active_only || entry.alert.is_active())
+            .filter(|entry| since.is_none_or(|since| entry.alert.fired_at >= since))
+            .collect();
+        alerts.sort_by_key(|entry| std::cmp::Reverse(entry.alert.fired_at));
+        alerts.truncate(args.limit);
+        let active = alerts.iter().filter(|entry| entry.alert.is_active()).count();
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let latest = alerts
+            .first()
+            .map_or_else(|| "none".to_string(), |entry| format!("{} ({})", entry.alert.rule, entry.alert.message));
+        let summary = style.summary(
+            "Alerts",
+            &format!(
+                "Alerts: {} · Active: {} · Rules: {}\nLatest: {}",
+                alerts.len(),
+                active,
+                rules.len(),
+                latest
+            ),
+        );
+
+        let output = InspectAlertsOutput {
+            success: true,
+            count: alerts.len(),
+            active,
+            rules,
+            alerts,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
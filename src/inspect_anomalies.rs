@@ -0,0 +1,154 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use std::time::Duration;
+
+use crate::anomaly::{self, DetectorConfig};
+use crate::export_session::parse_bound;
+use crate::history::{fetch_connections_history, resolve_connections};
+use crate::render::SummaryStyle;
+use crate::schema::inspect_anomalies::{InspectAnomaliesArgs, InspectAnomaliesOutput, INSPECT_ANOMALIES};
+
+/// Buckets with a value needed before a baseline is trusted
+const MIN_BASELINE_BUCKETS: usize = 5;
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct InspectAnomaliesTool;
+
+impl InspectAnomaliesTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for InspectAnomaliesTool {
+    type Args = InspectAnomaliesArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        INSPECT_ANOMALIES
+    }
+
+    fn description() -> &'static str {
+        "Find statistically unusual periods in tool call history. Calls are \
+         grouped into time buckets (bucket_secs) and three series are checked \
+         against a rolling baseline of the preceding buckets: calls per bucket \
+         (call_rate_spike), failed calls per bucket (failure_burst) and each \
+         tool's median latency (latency_regression).\n\n\
+         The baseline is an EWMA of the preceding baseline_buckets values and \
+         the spread is their median absolute deviation, so earlier outliers do \
+         not mask new ones. A bucket is flagged when (value - baseline) / spread \
+         exceeds threshold; consecutive flagged buckets are merged into one \
+         interval reported with its peak value, baseline, spread and score.\n\n\
+         Useful for:\n\
+         - Spotting when an agent started hammering a tool\n\
+         - Catching latency regressions after an environment change\n\
+         - Finding bursts of failures in a long session"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        if args.bucket_secs == 0 {
+            return Err(McpError::invalid_arguments("bucket_secs must be greater than 0"));
+        }
+        if args.baseline_buckets < MIN_BASELINE_BUCKETS {
+            return Err(McpError::invalid_arguments(format!("baseline_buckets must be at least {MIN_BASELINE_BUCKETS}")));
+        }
+        if !(args.alpha > 0.0 && args.alpha <= 1.0) {
+            return Err(McpError::invalid_arguments("alpha must be greater than 0 and at most 1"));
+        }
+        if args.threshold <= 0.0 {
+            return Err(McpError::invalid_arguments("threshold must be greater than 0"));
+        }
+        let since = parse_bound(args.since.as_deref(), "since")?;
+
+        // Other connections' history is subject to the access policy
+        let connections = resolve_connections(&ctx, args.connection_id, args.all_connections).await?;
+        let mut calls = fetch_connections_history(&ctx, &connections, args.all_connections)?.calls;
+        if let Some(tool_name) = &args.tool_name {
+            calls.retain(|call| &call.record.tool_name == tool_name);
+        }
+        if let Some(since) = since {
+            calls.retain(|call| call.time.is_some_and(|time| time >= since));
+        }
+
+        let width = Duration::from_secs(args.bucket_secs);
+        let buckets = anomaly::bucketize(&calls, width).map_err(|min_secs| {
+            McpError::invalid_arguments(format!(
+                "bucket_secs {} splits the history into more than {} buckets; use at least {min_secs} or narrow it with since",
+                args.bucket_secs,
+                anomaly::MAX_BUCKETS
+            ))
+        })?;
+        let config = DetectorConfig {
+            alpha: args.alpha,
+            baseline_buckets: args.baseline_buckets,
+            min_baseline: MIN_BASELINE_BUCKETS,
+            threshold: args.threshold,
+        };
+        let anomalies = anomaly::detect(&buckets, width, &config);
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let top = anomalies.first().map_or_else(
+            || "none".to_string(),
+            |top| {
+                format!(
+                    "{}{} at {} ({:.1} vs baseline {:.1}, score {:.1})",
+                    top.kind.as_str(),
+                    top.tool_name.as_ref().map(|tool| format!(" of {tool}")).unwrap_or_default(),
+                    top.start,
+                    top.value,
+                    top.baseline,
+                    top.score
+                )
+            },
+        );
+        let summary = style.summary(
+            "Anomalies",
+            &format!(
+                "Anomalies: {} · Calls: {} · Buckets: {} × {}s\nStrongest: {}",
+                anomalies.len(),
+                calls.len(),
+                buckets.len(),
+                args.bucket_secs,
+                top
+            ),
+        );
+
+        let output = InspectAnomaliesOutput {
+            success: true,
+            calls_scanned: calls.len(),
+            buckets: buckets.len(),
+            bucket_secs: args.bucket_secs,
+            count: anomalies.len(),
+            anomalies,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
@@ -0,0 +1,129 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectUsageStatsPrompts;
+
+use crate::budget::{self, BudgetConfig};
+use crate::history::{connection_id, resolve_connections};
+use crate::render::SummaryStyle;
+use crate::schema::inspect_budget::{InspectBudgetArgs, InspectBudgetOutput, SessionBudget, INSPECT_BUDGET};
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct InspectBudgetTool;
+
+impl InspectBudgetTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for InspectBudgetTool {
+    type Args = InspectBudgetArgs;
+    type Prompts = InspectUsageStatsPrompts;
+
+    fn name() -> &'static str {
+        INSPECT_BUDGET
+    }
+
+    fn description() -> &'static str {
+        "Show how much of the per-session budget in introspection-budget.json \
+         a connection's current session has used: calls per tool, total call \
+         duration and output bytes, with the remaining amount and whether a \
+         limit is reached. Servers enforce the same budgets through \
+         check_budget before executing a call.\n\n\
+         Usage is computed from the tool history of the calling connection by \
+         default; set connection_id for a sibling connection or all_connections \
+         for every known connection, both subject to the access policy. \
+         Sessions that timed out count as unused.\n\n\
+         Useful for:\n\
+         - Checking how close an agent is to its caps before a long task\n\
+         - Finding runaway agents that exhausted a budget\n\
+         - Tuning budget limits"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        let config = BudgetConfig::load()?;
+
+        // Other connections' usage is subject to the access policy
+        let requester = connection_id(&ctx)?;
+        let connections = resolve_connections(&ctx, args.connection_id, args.all_connections).await?;
+
+        let mut budgets = Vec::new();
+        for connection_id in connections {
+            let usage = match budget::connection_usage(&connection_id) {
+                Ok(usage) => usage,
+                // Listed connections may have disconnected since
+                Err(e) if args.all_connections && connection_id != requester => {
+                    log::debug!("Skipping budget of connection {connection_id}: {e}");
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let mut status = config.status(&usage);
+            if let Some(tool_name) = &args.tool_name {
+                status.limits.retain(|limit| limit.applies_to(tool_name));
+                status.exhausted = status.limits.iter().any(|limit| limit.exhausted);
+            }
+            budgets.push(SessionBudget { connection_id, status });
+        }
+        if args.exhausted_only {
+            budgets.retain(|budget| budget.status.exhausted);
+        }
+        let exhausted = budgets.iter().filter(|budget| budget.status.exhausted).count();
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let tightest = budgets
+            .iter()
+            .flat_map(|budget| budget.status.limits.iter())
+            .filter(|limit| limit.limit > 0)
+            .max_by(|a, b| (a.used as f64 / a.limit as f64).total_cmp(&(b.used as f64 / b.limit as f64)))
+            .map_or_else(|| "none".to_string(), |limit| limit.describe());
+        let summary = style.summary(
+            "Usage Budget",
+            &if config.is_empty() {
+                "No budget configured (introspection-budget.json)".to_string()
+            } else {
+                format!(
+                    "Sessions: {} · Exhausted: {}\nTightest limit: {}",
+                    budgets.len(),
+                    exhausted,
+                    tightest
+                )
+            },
+        );
+
+        let output = InspectBudgetOutput {
+            success: true,
+            configured: !config.is_empty(),
+            exhausted,
+            budgets,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
@@ -0,0 +1,207 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use serde_json::Value;
+
+use crate::activity::{self, Activity};
+use crate::anonymize;
+use crate::history::{connection_id, fetch_history, parse_timestamp, RecordedCall};
+use crate::redact::Redactor;
+use crate::render::SummaryStyle;
+use crate::schema::inspect_commands::{
+    CommandRecord, InspectCommandsArgs, InspectCommandsOutput, INSPECT_COMMANDS,
+};
+
+/// Output fields that carry captured command output, in preference order
+const OUTPUT_FIELDS: &[&str] = &["output", "stdout", "stderr", "display", "text", "message"];
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct InspectCommandsTool;
+
+impl InspectCommandsTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for InspectCommandsTool {
+    type Args = InspectCommandsArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        INSPECT_COMMANDS
+    }
+
+    fn description() -> &'static str {
+        "Get a shell-history-like log of commands run through terminal and process tools. \
+         Each entry has the command line, exit code, working directory, duration and \
+         output truncated to max_output_chars (default: 500).\n\n\
+         Useful for:\n\
+         - Auditing what an agent executed on a machine\n\
+         - Finding the command that broke the build\n\
+         - Re-running a sequence of commands by hand\n\n\
+         Filters: since (ISO 8601), failed_only. Returns the most recent max_results commands."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        let history = fetch_history(connection_id(&ctx)?)?;
+
+        let since = match args.since.as_deref() {
+            Some(since) => Some(parse_timestamp(since)
+                .ok_or_else(|| McpError::invalid_arguments(format!("Invalid since timestamp: {since}")))?),
+            None => None,
+        };
+
+        let mut commands: Vec<CommandRecord> = history.calls
+            .iter()
+            .filter(|call| activity::is_command_tool(&call.record.tool_name))
+            .filter(|call| match (since, call.time) {
+                (Some(since), Some(time)) => time >= since,
+                _ => true,
+            })
+            .map(command_record)
+            .filter(|command| !args.failed_only || command.failed)
+            .collect();
+
+        // Keep the most recent commands
+        let total_commands = commands.len();
+        if commands.len() > args.max_results {
+            commands.drain(..commands.len() - args.max_results);
+        }
+
+        // Mask secrets before any command leaves the tool
+        let redactor = Redactor::load()?;
+        let mut redactions = 0;
+        for command in &mut commands {
+            let (masked, count) = redactor.redact_text(&command.command);
+            command.command = masked;
+            redactions += count;
+            // Redact the full output: a secret cut at the limit would no longer match its detector
+            if let Some(output) = &command.output {
+                let (masked, count) = redactor.redact_text(output);
+                command.output = Some(masked);
+                redactions += count;
+            }
+            truncate_output(command, args.max_output_chars);
+        }
+
+        let history_view = commands
+            .iter()
+            .map(history_line)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let failed = commands.iter().filter(|command| command.failed).count();
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let summary = style.summary(
+            "Command History",
+            &format!(
+                "Commands: {} · Failed: {} · Latest: {}",
+                total_commands,
+                failed,
+                commands.last().map_or("none", |command| command.command.as_str())
+            ),
+        );
+
+        let output = InspectCommandsOutput {
+            success: true,
+            count: commands.len(),
+            total_commands,
+            commands,
+            history: history_view,
+            redactions,
+        };
+
+        // Pseudonymize machine details for shareable reports
+        let (summary, output) = anonymize::report(args.anonymize, summary, output)?;
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
+
+/// Extract the command, exit status and full captured output of a terminal/process call
+fn command_record(call: &RecordedCall) -> CommandRecord {
+    let output: Value = serde_json::from_str(&call.record.output_json).unwrap_or_default();
+
+    let command = activity::classify(call)
+        .into_iter()
+        .find_map(|activity| match activity {
+            Activity::Command(command) => Some(command),
+            _ => None,
+        })
+        .unwrap_or_else(|| format!("{} {}", call.record.tool_name, call.record.args_json));
+
+    let exit_code = output.get("exit_code").and_then(Value::as_i64);
+    let duration_ms = output
+        .get("duration_ms")
+        .and_then(Value::as_u64)
+        .or(call.record.duration_ms);
+
+    let captured = OUTPUT_FIELDS
+        .iter()
+        .filter_map(|key| output.get(key).and_then(Value::as_str))
+        .find(|text| !text.is_empty())
+        .map(str::to_string);
+
+    CommandRecord {
+        timestamp: call.record.timestamp.clone(),
+        tool_name: call.record.tool_name.clone(),
+        command,
+        exit_code,
+        cwd: output.get("cwd").and_then(Value::as_str).map(str::to_string),
+        duration_ms,
+        failed: call.is_failure() || exit_code.is_some_and(|code| code != 0),
+        output: captured,
+        output_truncated: false,
+    }
+}
+
+/// Cut the captured output to `max_output_chars` (0 drops it)
+fn truncate_output(command: &mut CommandRecord, max_output_chars: usize) {
+    let Some(output) = command.output.take() else {
+        return;
+    };
+    command.output_truncated = output.chars().count() > max_output_chars;
+    if max_output_chars > 0 {
+        command.output = Some(output.chars().take(max_output_chars).collect());
+    }
+}
+
+/// Render a command as a shell history line: `timestamp [exit N, 1.2s] command`
+fn history_line(command: &CommandRecord) -> String {
+    let exit = command
+        .exit_code
+        .map_or_else(|| "exit ?".to_string(), |code| format!("exit {code}"));
+    let duration = command
+        .duration_ms
+        .map_or_else(String::new, |ms| format!(", {:.1}s", ms as f64 / 1000.0));
+
+    format!("{} [{}{}] {}", command.timestamp, exit, duration, command.command)
+}
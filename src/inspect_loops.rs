@@ -0,0 +1,342 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::activity;
+use crate::history::{connection_id, fetch_history, parse_timestamp, RecordedCall};
+use crate::redact::Redactor;
+use crate::render::SummaryStyle;
+use crate::schema::inspect_loops::{
+    InspectLoopsArgs, InspectLoopsOutput, LoopCall, LoopFinding, LoopKind, INSPECT_LOOPS,
+};
+
+/// A call with its position in chronological history
+type IndexedCall<'a> = (usize, &'a RecordedCall);
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct InspectLoopsTool;
+
+impl InspectLoopsTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for InspectLoopsTool {
+    type Args = InspectLoopsArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        INSPECT_LOOPS
+    }
+
+    fn description() -> &'static str {
+        "Scan tool call history for loops and thrashing:\n\
+         - identical_calls: same tool with identical arguments, min_repeats times or more\n\
+         - near_identical_calls: arguments differing only in numbers, case or whitespace\n\
+         - oscillation: edits that undo an earlier edit, or writes restoring earlier content\n\
+         - retry_storm: repeated calls to a tool right after it failed\n\n\
+         Each finding lists the involved calls (completion timestamp and tool name) \
+         and a severity from 0.0 to 1.0, most severe first.\n\n\
+         Useful for:\n\
+         - Catching agents stuck repeating the same step\n\
+         - Spotting edit/undo cycles before they burn context\n\
+         - Finding flaky tools that trigger retries"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        if args.min_repeats < 2 {
+            return Err(McpError::invalid_arguments("min_repeats must be at least 2"));
+        }
+
+        let history = fetch_history(connection_id(&ctx)?)?;
+
+        let since = match args.since.as_deref() {
+            Some(since) => Some(parse_timestamp(since)
+                .ok_or_else(|| McpError::invalid_arguments(format!("Invalid since timestamp: {since}")))?),
+            None => None,
+        };
+
+        // Positions only order and deduplicate calls within this scan
+        let calls: Vec<IndexedCall<'_>> = history.calls
+            .iter()
+            .enumerate()
+            .filter(|(_, call)| match (since, call.time) {
+                (Some(since), Some(time)) => time >= since,
+                _ => true,
+            })
+            .collect();
+
+        let mut findings = detect_repeats(&calls, args.min_repeats);
+        findings.extend(detect_oscillation(&calls));
+        findings.extend(detect_retry_storms(&calls, args.min_repeats));
+        findings.sort_by(|a, b| b.severity.total_cmp(&a.severity));
+
+        // Descriptions quote paths taken from recorded arguments
+        let redactor = Redactor::load()?;
+        let mut redactions = 0;
+        for finding in &mut findings {
+            let (masked, count) = redactor.redact_text(&finding.description);
+            if count > 0 {
+                finding.description = masked;
+                redactions += count;
+            }
+        }
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let summary = match findings.first() {
+            Some(worst) => style.summary(
+                "Loop Detection",
+                &format!(
+                    "Calls: {} · Findings: {} · Worst: {} ({:.2})",
+                    calls.len(),
+                    findings.len(),
+                    worst.tool_name,
+                    worst.severity
+                ),
+            ),
+            None => style.summary("Loop Detection", &format!("Calls: {} · Findings: 0", calls.len())),
+        };
+
+        let output = InspectLoopsOutput {
+            success: true,
+            calls_scanned: calls.len(),
+            count: findings.len(),
+            findings,
+            redactions,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
+
+// ============================================================================
+// DETECTORS
+// ============================================================================
+
+fn finding(kind: LoopKind, calls: &[IndexedCall<'_>], severity: f64, description: String) -> LoopFinding {
+    LoopFinding {
+        kind,
+        tool_name: calls.first().map(|(_, call)| call.record.tool_name.clone()).unwrap_or_default(),
+        severity: severity.clamp(0.0, 1.0),
+        calls: calls
+            .iter()
+            .map(|(_, call)| LoopCall {
+                timestamp: call.record.timestamp.clone(),
+                tool_name: call.record.tool_name.clone(),
+            })
+            .collect(),
+        first_seen: calls.first().map(|(_, call)| call.record.timestamp.clone()).unwrap_or_default(),
+        last_seen: calls.last().map(|(_, call)| call.record.timestamp.clone()).unwrap_or_default(),
+        description,
+    }
+}
+
+/// Same tool called repeatedly with identical or near-identical arguments
+fn detect_repeats(calls: &[IndexedCall<'_>], min_repeats: usize) -> Vec<LoopFinding> {
+    let mut exact: HashMap<(String, String), Vec<IndexedCall<'_>>> = HashMap::new();
+    let mut near: HashMap<(String, String), Vec<IndexedCall<'_>>> = HashMap::new();
+
+    for &(idx, call) in calls {
+        let args = call.args();
+        let tool_name = call.record.tool_name.clone();
+        exact.entry((tool_name.clone(), args.to_string())).or_default().push((idx, call));
+        near.entry((tool_name, normalize(&args).to_string())).or_default().push((idx, call));
+    }
+
+    let mut findings = Vec::new();
+    let mut exact_groups: Vec<Vec<usize>> = Vec::new();
+
+    for group in exact.into_values().filter(|group| group.len() >= min_repeats) {
+        let failed = group.iter().filter(|(_, call)| call.is_failure()).count();
+        let severity = (group.len() - 1) as f64 / 10.0 + if failed == group.len() { 0.2 } else { 0.0 };
+        exact_groups.push(group.iter().map(|(idx, _)| *idx).collect());
+        findings.push(finding(
+            LoopKind::IdenticalCalls,
+            &group,
+            severity,
+            format!("Called {} times with identical arguments ({} failed)", group.len(), failed),
+        ));
+    }
+
+    for group in near.into_values().filter(|group| group.len() >= min_repeats) {
+        // Covers calls already reported as identical calls
+        let ids: Vec<usize> = group.iter().map(|(idx, _)| *idx).collect();
+        if exact_groups.iter().any(|exact| exact.iter().all(|idx| ids.contains(idx))) {
+            continue;
+        }
+
+        findings.push(finding(
+            LoopKind::NearIdenticalCalls,
+            &group,
+            (group.len() - 1) as f64 / 10.0 * 0.7,
+            format!("Called {} times with arguments differing only in numbers, case or whitespace", group.len()),
+        ));
+    }
+
+    findings
+}
+
+/// Replace numbers and fold case/whitespace so near-identical arguments compare equal
+fn normalize(value: &Value) -> Value {
+    match value {
+        Value::Number(_) => Value::from(0),
+        Value::String(s) => Value::String(s.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()),
+        Value::Array(items) => Value::Array(items.iter().map(normalize).collect()),
+        Value::Object(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), normalize(v))).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Edits that revert an earlier edit, and writes that restore earlier content
+fn detect_oscillation(calls: &[IndexedCall<'_>]) -> Vec<LoopFinding> {
+    let mut edits: HashMap<String, Vec<(IndexedCall<'_>, String, String)>> = HashMap::new();
+    let mut writes: HashMap<String, Vec<(IndexedCall<'_>, u64)>> = HashMap::new();
+
+    for &(idx, call) in calls {
+        let args = call.args();
+        let Some(path) = activity::paths(&call.record.tool_name, &args).into_iter().next() else {
+            continue;
+        };
+
+        let old = args.get("old_string").and_then(Value::as_str);
+        let new = args.get("new_string").and_then(Value::as_str);
+        if let (Some(old), Some(new)) = (old, new) {
+            edits.entry(path).or_default().push(((idx, call), old.to_string(), new.to_string()));
+        } else if let Some(content) = args.get("content").and_then(Value::as_str) {
+            let mut hasher = DefaultHasher::new();
+            content.hash(&mut hasher);
+            writes.entry(path).or_default().push(((idx, call), hasher.finish()));
+        }
+    }
+
+    let mut findings = Vec::new();
+
+    for (path, edits) in edits {
+        let mut involved: Vec<IndexedCall<'_>> = Vec::new();
+        let mut cycles = 0;
+
+        for (j, (call_j, old_j, new_j)) in edits.iter().enumerate() {
+            let reverted = edits[..j]
+                .iter()
+                .rev()
+                .find(|(_, old_i, new_i)| old_j == new_i && new_j == old_i);
+            if let Some((call_i, _, _)) = reverted {
+                cycles += 1;
+                for call in [*call_i, *call_j] {
+                    if !involved.iter().any(|(idx, _)| *idx == call.0) {
+                        involved.push(call);
+                    }
+                }
+            }
+        }
+
+        if cycles > 0 {
+            involved.sort_by_key(|(idx, _)| *idx);
+            findings.push(finding(
+                LoopKind::Oscillation,
+                &involved,
+                0.4 + 0.2 * f64::from(cycles),
+                format!("{path}: {cycles} edit(s) undid an earlier edit"),
+            ));
+        }
+    }
+
+    for (path, writes) in writes {
+        let mut involved: Vec<IndexedCall<'_>> = Vec::new();
+        let mut cycles = 0;
+
+        for (j, (_, hash_j)) in writes.iter().enumerate() {
+            // Restores content written earlier, with different content in between
+            let earlier = writes[..j].iter().rposition(|(_, hash_i)| hash_i == hash_j);
+            if let Some(i) = earlier
+                && i + 1 < j
+            {
+                cycles += 1;
+                for (call, _) in &writes[i..=j] {
+                    if !involved.iter().any(|(idx, _)| *idx == call.0) {
+                        involved.push(*call);
+                    }
+                }
+            }
+        }
+
+        if cycles > 0 {
+            involved.sort_by_key(|(idx, _)| *idx);
+            findings.push(finding(
+                LoopKind::Oscillation,
+                &involved,
+                0.4 + 0.2 * f64::from(cycles),
+                format!("{path}: {cycles} write(s) restored earlier content"),
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Runs of consecutive calls to the same tool that start with a failure
+fn detect_retry_storms(calls: &[IndexedCall<'_>], min_repeats: usize) -> Vec<LoopFinding> {
+    let mut findings = Vec::new();
+    let mut start = 0;
+
+    while start < calls.len() {
+        let tool_name = &calls[start].1.record.tool_name;
+        let end = calls[start..]
+            .iter()
+            .position(|(_, call)| &call.record.tool_name != tool_name)
+            .map_or(calls.len(), |len| start + len);
+
+        let run = &calls[start..end];
+        let failed = run.iter().filter(|(_, call)| call.is_failure()).count();
+
+        if run.len() >= min_repeats && failed >= 2 && run[0].1.is_failure() {
+            let still_failing = run.last().is_some_and(|(_, call)| call.is_failure());
+            findings.push(finding(
+                LoopKind::RetryStorm,
+                run,
+                failed as f64 / 6.0 + if still_failing { 0.2 } else { 0.0 },
+                format!(
+                    "{} consecutive calls after a failure, {} failed{}",
+                    run.len(),
+                    failed,
+                    if still_failing { ", last call still failing" } else { "" }
+                ),
+            ));
+        }
+
+        start = end;
+    }
+
+    findings
+}
@@ -0,0 +1,403 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use std::time::Duration;
+
+use crate::activity::{self, Activity};
+use crate::anonymize;
+use crate::context_cost::{CharRatioEstimator, TokenEstimator};
+use crate::history::{self, connection_id, fetch_history, RecordedCall};
+use crate::redact::Redactor;
+use crate::render::SummaryStyle;
+use crate::schema::inspect_session_summary::{
+    InspectSessionSummaryArgs, InspectSessionSummaryOutput, PhaseKind, SummaryError, SummaryPhase,
+    INSPECT_SESSION_SUMMARY,
+};
+use crate::sessions;
+
+/// Per-list item limits tried in order until the narrative fits the budget
+const ITEM_LIMITS: &[usize] = &[20, 10, 5, 3, 1, 0];
+
+/// Maximum characters kept from an error message
+const MAX_ERROR_CHARS: usize = 160;
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct InspectSessionSummaryTool;
+
+impl InspectSessionSummaryTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for InspectSessionSummaryTool {
+    type Args = InspectSessionSummaryArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        INSPECT_SESSION_SUMMARY
+    }
+
+    fn description() -> &'static str {
+        "Summarize tool call history as a structured narrative instead of raw JSON. \
+         Reports files read, written and edited, commands run, searches performed \
+         and errors hit, grouped into work phases (explore, modify, execute). \
+         The narrative is shortened to fit max_tokens (default: 2000).\n\n\
+         Useful for:\n\
+         - Onboarding new chats about work already done\n\
+         - Recovering context after chat history loss without replaying raw outputs\n\
+         - Handing work off between agents\n\n\
+         Pass session_id (from inspect_sessions) to summarize a single session."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        if args.timeout_minutes == 0 {
+            return Err(McpError::invalid_arguments("timeout_minutes must be greater than 0"));
+        }
+
+        let history = fetch_history(connection_id(&ctx)?)?;
+        let timeout = Duration::from_secs(args.timeout_minutes.saturating_mul(60));
+
+        // Restrict to the requested session
+        let calls = match args.session_id.as_deref() {
+            Some(session_id) => {
+                let (_, range) = sessions::segment(&history.calls, timeout)
+                    .into_iter()
+                    .find(|(session, _)| session.id == session_id)
+                    .ok_or_else(|| McpError::resource_not_found(format!("No session with id: {session_id}")))?;
+                &history.calls[range]
+            }
+            None => &history.calls[..],
+        };
+
+        // Commands, searches and error messages can quote secrets
+        let redactor = Redactor::load()?;
+        let mut digest = Digest::default();
+        for call in calls {
+            digest.add(call, &activity::classify(call), &redactor);
+        }
+        let mut phases = build_phases(calls, timeout);
+        let mut redactions = digest.redactions;
+        for highlight in phases.iter_mut().flat_map(|phase| phase.highlights.iter_mut()) {
+            let (masked, count) = redactor.redact_text(highlight);
+            if count > 0 {
+                *highlight = masked;
+                redactions += count;
+            }
+        }
+        let total_phases = phases.len();
+        let failed_calls = calls.iter().filter(|call| call.is_failure()).count();
+
+        // Shorten lists until the narrative fits the token budget
+        let mut limit = ITEM_LIMITS[0];
+        let mut narrative = String::new();
+        for &candidate in ITEM_LIMITS {
+            limit = candidate;
+            narrative = render(&digest, &phases, calls.len(), failed_calls, limit);
+            if estimate_tokens(&narrative) <= args.max_tokens {
+                break;
+            }
+        }
+
+        // Still too long: drop the oldest phases, then cut the text
+        while estimate_tokens(&narrative) > args.max_tokens && !phases.is_empty() {
+            phases.remove(0);
+            narrative = render(&digest, &phases, calls.len(), failed_calls, limit);
+        }
+        if estimate_tokens(&narrative) > args.max_tokens {
+            narrative = narrative.chars().take(args.max_tokens.saturating_mul(4)).collect();
+        }
+        let (masked, count) = redactor.redact_text(&narrative);
+        narrative = masked;
+        redactions += count;
+
+        let truncated = limit < ITEM_LIMITS[0] || phases.len() < total_phases;
+        if truncated {
+            digest.truncate(limit);
+            for phase in &mut phases {
+                phase.highlights.truncate(limit);
+            }
+        }
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let summary = style.summary(
+            "Session Summary",
+            &format!(
+                "Calls: {} · Files changed: {} · Commands: {} · Errors: {}",
+                calls.len(),
+                digest.files_written.len() + digest.files_edited.len(),
+                digest.commands.len(),
+                failed_calls
+            ),
+        );
+
+        let output = InspectSessionSummaryOutput {
+            success: true,
+            session_id: args.session_id,
+            total_calls: calls.len(),
+            failed_calls,
+            files_read: digest.files_read,
+            files_written: digest.files_written,
+            files_edited: digest.files_edited,
+            commands: digest.commands,
+            searches: digest.searches,
+            errors: digest.errors,
+            phases,
+            estimated_tokens: estimate_tokens(&narrative),
+            narrative,
+            max_tokens: args.max_tokens,
+            truncated,
+            redactions,
+        };
+
+        // Pseudonymize machine details for shareable reports
+        let (summary, output) = anonymize::report(args.anonymize, summary, output)?;
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
+
+// ============================================================================
+// DIGEST
+// ============================================================================
+
+/// Deduplicated activity lists, in first-occurrence order
+#[derive(Default)]
+struct Digest {
+    files_read: Vec<String>,
+    files_written: Vec<String>,
+    files_edited: Vec<String>,
+    commands: Vec<String>,
+    searches: Vec<String>,
+    errors: Vec<SummaryError>,
+    /// Secrets masked in commands, searches and error messages
+    redactions: usize,
+}
+
+impl Digest {
+    fn add(&mut self, call: &RecordedCall, activities: &[Activity], redactor: &Redactor) {
+        for activity in activities {
+            match activity {
+                Activity::Read(path) => push_unique(&mut self.files_read, path),
+                Activity::Write(path) => push_unique(&mut self.files_written, path),
+                Activity::Edit(path) => push_unique(&mut self.files_edited, path),
+                Activity::Command(command) => {
+                    let command = self.redact(redactor, command);
+                    push_unique(&mut self.commands, &command);
+                }
+                Activity::Search(query) => {
+                    let query = self.redact(redactor, query);
+                    push_unique(&mut self.searches, &query);
+                }
+            }
+        }
+
+        if call.is_failure() {
+            // Mask before cutting so a secret across the cut is still caught
+            let message = history::failure_message(&call.record.output_json)
+                .map_or_else(|| "failed".to_string(), |message| self.redact(redactor, &message));
+            self.errors.push(SummaryError {
+                tool_name: call.record.tool_name.clone(),
+                timestamp: call.record.timestamp.clone(),
+                message: message.chars().take(MAX_ERROR_CHARS).collect(),
+            });
+        }
+    }
+
+    fn redact(&mut self, redactor: &Redactor, text: &str) -> String {
+        let (masked, count) = redactor.redact_text(text);
+        self.redactions += count;
+        masked
+    }
+
+    fn truncate(&mut self, limit: usize) {
+        self.files_read.truncate(limit);
+        self.files_written.truncate(limit);
+        self.files_edited.truncate(limit);
+        self.commands.truncate(limit);
+        self.searches.truncate(limit);
+        self.errors.truncate(limit);
+    }
+}
+
+fn push_unique(list: &mut Vec<String>, item: &str) {
+    if !list.iter().any(|existing| existing == item) {
+        list.push(item.to_string());
+    }
+}
+
+// ============================================================================
+// PHASES
+// ============================================================================
+
+/// Kind of work a call represents (None for unclassified calls)
+fn phase_kind(activities: &[Activity]) -> Option<PhaseKind> {
+    activities.first().map(|activity| match activity {
+        Activity::Read(_) | Activity::Search(_) => PhaseKind::Explore,
+        Activity::Write(_) | Activity::Edit(_) => PhaseKind::Modify,
+        Activity::Command(_) => PhaseKind::Execute,
+    })
+}
+
+fn highlight(activity: &Activity) -> String {
+    match activity {
+        Activity::Read(path) => format!("read {path}"),
+        Activity::Write(path) => format!("wrote {path}"),
+        Activity::Edit(path) => format!("edited {path}"),
+        Activity::Command(command) => format!("ran `{command}`"),
+        Activity::Search(query) => format!("searched \"{query}\""),
+    }
+}
+
+/// Group calls into contiguous phases of the same kind of work
+///
+/// A new phase starts when the kind of work changes or a session boundary
+/// is crossed. Unclassified calls join the current phase.
+fn build_phases(calls: &[RecordedCall], timeout: Duration) -> Vec<SummaryPhase> {
+    let mut phases: Vec<SummaryPhase> = Vec::new();
+    let mut last_time: Option<i64> = None;
+
+    for call in calls {
+        let activities = activity::classify(call);
+        let kind = phase_kind(&activities);
+        let at = call.time.map(|t| t.timestamp());
+
+        let session_break = matches!((last_time, at), (Some(last), Some(now)) if sessions::is_new_session(last, now, timeout));
+        let kind_change = match (phases.last(), kind) {
+            (Some(phase), Some(kind)) => phase.kind != PhaseKind::Other && phase.kind != kind,
+            _ => false,
+        };
+
+        if phases.is_empty() || session_break || kind_change {
+            phases.push(SummaryPhase {
+                kind: kind.unwrap_or(PhaseKind::Other),
+                start: call.record.timestamp.clone(),
+                end: call.record.timestamp.clone(),
+                call_count: 0,
+                failed_calls: 0,
+                highlights: Vec::new(),
+            });
+        }
+
+        if let Some(phase) = phases.last_mut() {
+            if phase.kind == PhaseKind::Other
+                && let Some(kind) = kind
+            {
+                phase.kind = kind;
+            }
+            phase.end = call.record.timestamp.clone();
+            phase.call_count += 1;
+            if call.is_failure() {
+                phase.failed_calls += 1;
+            }
+            for activity in &activities {
+                push_unique(&mut phase.highlights, &highlight(activity));
+            }
+        }
+
+        last_time = at.or(last_time);
+    }
+
+    phases
+}
+
+// ============================================================================
+// NARRATIVE
+// ============================================================================
+
+/// Rough token estimate (~4 characters per token)
+fn estimate_tokens(text: &str) -> usize {
+    CharRatioEstimator::default().estimate(text) as usize
+}
+
+fn render(digest: &Digest, phases: &[SummaryPhase], total_calls: usize, failed_calls: usize, limit: usize) -> String {
+    let mut out = format!("{total_calls} tool calls ({failed_calls} failed).\n");
+
+    list_line(&mut out, "Files read", &digest.files_read, limit);
+    list_line(&mut out, "Files written", &digest.files_written, limit);
+    list_line(&mut out, "Files edited", &digest.files_edited, limit);
+    list_line(&mut out, "Commands run", &digest.commands, limit);
+    list_line(&mut out, "Searches", &digest.searches, limit);
+
+    let errors: Vec<String> = digest.errors
+        .iter()
+        .map(|error| format!("{}: {}", error.tool_name, error.message))
+        .collect();
+    list_line(&mut out, "Errors", &errors, limit);
+
+    if !phases.is_empty() {
+        out.push_str("Phases:\n");
+        for (idx, phase) in phases.iter().enumerate() {
+            let kind = match phase.kind {
+                PhaseKind::Explore => "Explore",
+                PhaseKind::Modify => "Modify",
+                PhaseKind::Execute => "Execute",
+                PhaseKind::Other => "Other",
+            };
+            out.push_str(&format!(
+                "{}. {} {} → {} · {} calls",
+                idx + 1,
+                kind,
+                phase.start,
+                phase.end,
+                phase.call_count
+            ));
+            if phase.failed_calls > 0 {
+                out.push_str(&format!(" ({} failed)", phase.failed_calls));
+            }
+            if limit > 0 && !phase.highlights.is_empty() {
+                out.push_str(": ");
+                out.push_str(&join_limited(&phase.highlights, limit));
+            }
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+fn list_line(out: &mut String, label: &str, items: &[String], limit: usize) {
+    if items.is_empty() {
+        return;
+    }
+    if limit == 0 {
+        out.push_str(&format!("{label}: {}\n", items.len()));
+    } else {
+        out.push_str(&format!("{label} ({}): {}\n", items.len(), join_limited(items, limit)));
+    }
+}
+
+fn join_limited(items: &[String], limit: usize) -> String {
+    let shown = items.iter().take(limit).cloned().collect::<Vec<_>>().join(", ");
+    if items.len() > limit {
+        format!("{shown} (+{} more)", items.len() - limit)
+    } else {
+        shown
+    }
+}
@@ -0,0 +1,124 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use std::time::Duration;
+
+use crate::history::{connection_id, fetch_history};
+use crate::redact::Redactor;
+use crate::render::SummaryStyle;
+use crate::schema::inspect_sessions::{InspectSessionsArgs, InspectSessionsOutput, INSPECT_SESSIONS};
+use crate::sessions;
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct InspectSessionsTool;
+
+impl InspectSessionsTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for InspectSessionsTool {
+    type Args = InspectSessionsArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        INSPECT_SESSIONS
+    }
+
+    fn description() -> &'static str {
+        "List tool call sessions for this connection. A session is a run of calls \
+         with no inactivity gap longer than timeout_minutes (default: 30). \
+         Each session reports its id, start/end time, call count, failures and tools used.\n\n\
+         Pass session_id to drill into a single session and get its calls \
+         (secrets in arguments and outputs masked as [REDACTED:<kind>]).\n\n\
+         Useful for:\n\
+         - Seeing how work was split across sittings\n\
+         - Finding the session where failures clustered\n\
+         - Scoping other inspections to one session's time range"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        if args.timeout_minutes == 0 {
+            return Err(McpError::invalid_arguments("timeout_minutes must be greater than 0"));
+        }
+
+        let history = fetch_history(connection_id(&ctx)?)?;
+        let timeout = Duration::from_secs(args.timeout_minutes.saturating_mul(60));
+        let segments = sessions::segment(&history.calls, timeout);
+
+        // Drill into the requested session, masking secrets before any record leaves the tool
+        let mut redactions = 0;
+        let session_calls = match args.session_id.as_deref() {
+            Some(session_id) => {
+                let (_, range) = segments
+                    .iter()
+                    .find(|(session, _)| session.id == session_id)
+                    .ok_or_else(|| McpError::resource_not_found(format!("No session with id: {session_id}")))?;
+
+                let redactor = Redactor::load()?;
+                let mut calls: Vec<_> = history.calls[range.clone()].iter().map(|call| call.record.clone()).collect();
+                redactions = calls.iter_mut().map(|record| redactor.redact_record(record)).sum();
+                Some(calls)
+            }
+            None => None,
+        };
+
+        let sessions: Vec<_> = segments.into_iter().map(|(session, _)| session).collect();
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let summary = match (&session_calls, sessions.last()) {
+            (Some(calls), _) => style.summary(
+                &format!("Session {}", args.session_id.as_deref().unwrap_or_default()),
+                &format!("Calls: {} · Redacted: {}", calls.len(), redactions),
+            ),
+            (None, Some(latest)) => style.summary(
+                "Sessions",
+                &format!(
+                    "Sessions: {} · Latest: {} ({} calls)",
+                    sessions.len(),
+                    latest.id,
+                    latest.call_count
+                ),
+            ),
+            (None, None) => style.summary("Sessions", "Sessions: 0 · No tool calls recorded"),
+        };
+
+        let output = InspectSessionsOutput {
+            success: true,
+            count: sessions.len(),
+            timeout_minutes: args.timeout_minutes,
+            sessions,
+            session_calls,
+            redactions,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
@@ -1,8 +1,16 @@
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
 use kodegen_mcp_schema::McpError;
-use kodegen_mcp_schema::introspection::{InspectToolCallsArgs, InspectToolCallsOutput, InspectToolCallsPrompts, ToolCallRecord, INSPECT_TOOL_CALLS};
-use kodegend_client_ipc::get_tool_history;
+use kodegen_mcp_schema::introspection::{InspectToolCallsPrompts, INSPECT_TOOL_CALLS};
 
+use crate::anonymize;
+use crate::export;
+use crate::history::{fetch_authorized_history, fetch_connections_history, known_connections, RecordedCall};
+use crate::redact::Redactor;
+use crate::render::SummaryStyle;
+use crate::schema::inspect_tool_calls::{ConnectionCall, InspectToolCallsArgs, InspectToolCallsOutput, ToolCallsFormat};
+
+/// Longest args/output cell in Markdown tables
+const MARKDOWN_CELL_CHARS: usize = 80;
 
 // ============================================================================
 // TOOL STRUCT
@@ -39,6 +47,15 @@ impl Tool for InspectToolCallsTool {
          - Recovering context after chat history loss\n\
          - Debugging tool call sequences\n\
          - Navigating large tool histories with pagination\n\n\
+         Set format to \"jsonl\", \"csv\" or \"markdown\" to get the selected calls \
+         ready to paste into reports, or \"chrome_trace\" for Chrome Trace Event JSON \
+         (one track per server) to open in chrome://tracing or Perfetto.\n\n\
+         Set connection_id to inspect a sibling connection, or all_connections \
+         to merge every known connection's history (each call is tagged with its \
+         connection_id); both are subject to the access policy.\n\n\
+         Secrets in arguments and outputs (API keys, tokens, passwords, \
+         high-entropy strings) are masked as [REDACTED:<kind>]; rules are \
+         configured in introspection-redaction.json.\n\n\
          Note: Does not track its own calls or other meta/query tools. \
          History kept in memory (last 1000 calls, persisted to disk)."
     }
@@ -60,39 +77,44 @@ impl Tool for InspectToolCallsTool {
     }
 
     async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
-        // Get connection ID from context
-        let connection_id = ctx.connection_id()
-            .ok_or_else(|| McpError::Other(anyhow::anyhow!("No connection ID available - tool history requires connection context")))?;
+        let format = args.format;
+        let style = SummaryStyle::resolve(args.style);
+        let anonymize = args.anonymize;
+        let all_connections = args.all_connections;
+
+        // Other connections' history is subject to the access policy
+        let connections = match (args.connection_id, args.all_connections) {
+            (Some(_), true) => {
+                return Err(McpError::invalid_arguments("connection_id and all_connections are mutually exclusive"));
+            }
+            (Some(connection_id), false) => vec![connection_id],
+            (None, true) => known_connections(&ctx).await?,
+            (None, false) => Vec::new(),
+        };
+        let cross_connection = !connections.is_empty();
+        let args = args.base;
 
         // Query kodegend daemon via IPC for aggregated tool history
-        let history = get_tool_history(connection_id)
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to query tool history from kodegend: {}", e)))?;
+        let history = if cross_connection {
+            fetch_connections_history(&ctx, &connections, all_connections)?
+        } else {
+            fetch_authorized_history(&ctx, None)?
+        };
 
-        // Flatten all calls from all servers and map IPC types to schema types
-        let mut all_calls: Vec<ToolCallRecord> = history.servers
-            .into_iter()
-            .flat_map(|server| server.calls)
-            .map(|ipc_call| ToolCallRecord {
-                tool_name: ipc_call.tool_name,
-                timestamp: ipc_call.timestamp,
-                duration_ms: ipc_call.duration_ms,
-                args_json: ipc_call.args_json,
-                output_json: ipc_call.output_json,
-            })
-            .collect();
+        let mut all_calls: Vec<RecordedCall> = history.calls;
 
         // Apply tool name filter
         if let Some(ref tool_name) = args.tool_name {
-            all_calls.retain(|call| &call.tool_name == tool_name);
+            all_calls.retain(|call| &call.record.tool_name == tool_name);
         }
 
         // Apply timestamp filter (since)
         if let Some(ref since) = args.since {
-            all_calls.retain(|call| call.timestamp >= *since);
+            all_calls.retain(|call| call.record.timestamp >= *since);
         }
 
         // Sort by timestamp descending (newest first)
-        all_calls.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        all_calls.sort_by(|a, b| b.record.timestamp.cmp(&a.record.timestamp));
 
         // Apply offset and limit
         let offset = args.offset;
@@ -106,41 +128,67 @@ impl Tool for InspectToolCallsTool {
             offset as usize
         };
 
-        let calls: Vec<ToolCallRecord> = all_calls
+        let mut calls: Vec<RecordedCall> = all_calls
             .into_iter()
             .skip(start_idx)
             .take(max_results)
             .collect();
 
-        // Terminal formatted summary
+        // Mask secrets before any record leaves the tool
+        let redactor = Redactor::load()?;
+        let redactions: usize = calls
+            .iter_mut()
+            .map(|call| redactor.redact_record(&mut call.record))
+            .sum();
+
+        // Summary in the requested style
         let summary = if calls.is_empty() {
-            "\x1b[35mTool Call History\x1b[0m\n\
-             Calls: 0 · No calls matching criteria".to_string()
+            style.summary("Tool Call History", "Calls: 0 · No calls matching criteria")
         } else {
             let latest_tool = calls.first()
-                .map(|c| c.tool_name.as_str())
+                .map(|c| c.record.tool_name.as_str())
                 .unwrap_or("unknown");
 
-            format!(
-                "\x1b[35mTool Call History\x1b[0m\n\
-                 Calls: {} · Latest: {}",
-                calls.len(),
-                latest_tool
-            )
+            let mut detail = format!("Calls: {} · Latest: {} · Redacted: {}", calls.len(), latest_tool, redactions);
+            if cross_connection {
+                detail.push_str(&format!(" · Connections: {}", connections.len()));
+            }
+            style.summary("Tool Call History", &detail)
+        };
+
+        // Export formats replace the summary as display output
+        let display = match format {
+            ToolCallsFormat::Json => summary,
+            ToolCallsFormat::Jsonl => export::tool_calls_jsonl(&calls, cross_connection),
+            ToolCallsFormat::Csv => export::tool_calls_table(&calls, 0, cross_connection).to_csv(),
+            ToolCallsFormat::Markdown => export::tool_calls_table(&calls, MARKDOWN_CELL_CHARS, cross_connection).to_markdown(),
+            ToolCallsFormat::ChromeTrace => export::chrome_trace(&calls, cross_connection).to_string(),
         };
 
         let output = InspectToolCallsOutput {
             success: true,
             count: calls.len(),
             total_entries_in_memory: history.total_calls,
-            calls,
+            calls: calls
+                .into_iter()
+                .map(|call| ConnectionCall {
+                    connection_id: call.connection_id.filter(|_| cross_connection),
+                    record: call.record,
+                })
+                .collect(),
             filter_tool_name: args.tool_name,
             filter_since: args.since,
             offset: args.offset,
             max_results: args.max_results,
+            format,
+            redactions,
+            connections,
         };
 
-        Ok(ToolResponse::new(summary, output))
+        // Pseudonymize machine details for shareable reports
+        let (display, output) = anonymize::report(anonymize, display, output)?;
+
+        Ok(ToolResponse::new(display, output))
     }
 
 }
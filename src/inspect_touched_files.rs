@@ -0,0 +1,160 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use std::collections::HashMap;
+
+use crate::activity::{self, Activity};
+use crate::anonymize;
+use crate::history::{connection_id, fetch_history, parse_timestamp, RecordedCall};
+use crate::render::SummaryStyle;
+use crate::schema::inspect_touched_files::{
+    InspectTouchedFilesArgs, InspectTouchedFilesOutput, TouchedFile, INSPECT_TOUCHED_FILES,
+};
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct InspectTouchedFilesTool;
+
+impl InspectTouchedFilesTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for InspectTouchedFilesTool {
+    type Args = InspectTouchedFilesArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        INSPECT_TOUCHED_FILES
+    }
+
+    fn description() -> &'static str {
+        "List files touched by filesystem and edit tools during this connection. \
+         Path arguments are found using each tool's argument schema. Each path \
+         reports read, write and edit counts plus when and by which tool it was last touched.\n\n\
+         Useful for:\n\
+         - Answering \"which files did I modify?\"\n\
+         - Reviewing the footprint of a change before committing\n\
+         - Finding files that were read but never changed\n\n\
+         Filters: path_prefix, modified_only, since (ISO 8601)."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        let history = fetch_history(connection_id(&ctx)?)?;
+
+        // Apply timestamp filter (since)
+        let since = match args.since.as_deref() {
+            Some(since) => Some(parse_timestamp(since)
+                .ok_or_else(|| McpError::invalid_arguments(format!("Invalid since timestamp: {since}")))?),
+            None => None,
+        };
+        let calls: Vec<RecordedCall> = history.calls
+            .into_iter()
+            .filter(|call| match (since, call.time) {
+                (Some(since), Some(time)) => time >= since,
+                _ => true,
+            })
+            .collect();
+
+        let mut files = index_touched_files(&calls);
+
+        if let Some(ref prefix) = args.path_prefix {
+            files.retain(|file| file.path.starts_with(prefix.as_str()));
+        }
+        if args.modified_only {
+            files.retain(|file| file.writes + file.edits > 0);
+        }
+
+        let total_files = files.len();
+        files.truncate(args.max_results);
+
+        let modified = files.iter().filter(|file| file.writes + file.edits > 0).count();
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let summary = style.summary(
+            "Touched Files",
+            &format!(
+                "Files: {} · Modified: {} · Read only: {}",
+                total_files,
+                modified,
+                files.len() - modified
+            ),
+        );
+
+        let output = InspectTouchedFilesOutput {
+            success: true,
+            count: files.len(),
+            total_files,
+            files,
+        };
+
+        // Pseudonymize machine details for shareable reports
+        let (summary, output) = anonymize::report(args.anonymize, summary, output)?;
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
+
+/// Index every path touched in `calls`, most recently touched first
+pub(crate) fn index_touched_files(calls: &[RecordedCall]) -> Vec<TouchedFile> {
+    let mut index: HashMap<String, TouchedFile> = HashMap::new();
+
+    for call in calls {
+        for activity in activity::classify(call) {
+            let path = match &activity {
+                Activity::Read(path) | Activity::Write(path) | Activity::Edit(path) => path.clone(),
+                Activity::Command(_) | Activity::Search(_) => continue,
+            };
+
+            let file = index.entry(path.clone()).or_insert_with(|| TouchedFile {
+                path,
+                reads: 0,
+                writes: 0,
+                edits: 0,
+                last_touched: String::new(),
+                last_tool: String::new(),
+            });
+
+            match activity {
+                Activity::Read(_) => file.reads += 1,
+                Activity::Write(_) => file.writes += 1,
+                Activity::Edit(_) => file.edits += 1,
+                Activity::Command(_) | Activity::Search(_) => {}
+            }
+
+            // Calls are chronological, so the latest call wins
+            file.last_touched = call.record.timestamp.clone();
+            file.last_tool = call.record.tool_name.clone();
+        }
+    }
+
+    let mut files: Vec<TouchedFile> = index.into_values().collect();
+    files.sort_by(|a, b| b.last_touched.cmp(&a.last_touched).then_with(|| a.path.cmp(&b.path)));
+    files
+}
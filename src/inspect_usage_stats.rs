@@ -1,10 +1,21 @@
 use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
 use kodegen_mcp_schema::McpError;
 use kodegen_mcp_schema::introspection::{
-    InspectUsageStatsArgs, InspectUsageOutput, InspectUsageStatsPrompts,
-    ToolUsageStats, INSPECT_USAGE_STATS,
+    self, InspectUsageStatsPrompts, ToolUsageStats, INSPECT_USAGE_STATS,
 };
 use kodegend_client_ipc::get_usage_stats;
+use std::collections::HashMap;
+
+use crate::access::AccessPolicy;
+use crate::anonymize;
+use crate::context_cost::{tool_costs, TokenEstimator, ToolCost};
+use crate::export::Table;
+use crate::history::{connection_id, fetch_history, known_connections, load_disk_history};
+use crate::render::SummaryStyle;
+use crate::schema::inspect_usage_stats::{
+    ConnectionUsage, InspectUsageStatsArgs, InspectUsageOutput, InstanceUsage, ToolContextCost, UsageFormat, UsageScope,
+};
+use crate::usage_tracker::{UsageStats, UsageTracker};
 
 // ============================================================================
 // TOOL STRUCT
@@ -41,6 +52,23 @@ impl Tool for InspectUsageStatsTool {
          - Analyzing performance and success rates\n\
          - Debugging tool execution issues\n\
          - Understanding which tools are most frequently used\n\n\
+         Scope: \"connection\" (default) reports the current connection, \
+         \"instance\" reads one instance's stats file (requires instance_id), \
+         \"all_instances\" merges every instance's stats file into lifetime totals \
+         with a per-instance breakdown. The instance scopes include other \
+         connections' data and are denied under the \"own_connection\" access \
+         policy; under \"same_user\" only stats files owned by the same OS user \
+         are read.\n\n\
+         With the connection scope, connection_id reports a sibling connection \
+         and all_connections merges every known connection with a \
+         per-connection breakdown, subject to the same policy.\n\n\
+         Context costs rank tools by the bytes and estimated tokens their \
+         arguments and outputs consume. They are measured from tool history \
+         (kodegend's for the connection scope, the persisted history files \
+         for the instance scopes) using the tokenizer heuristic \
+         (\"chars\" or \"words\").\n\n\
+         Set format to \"jsonl\", \"csv\" or \"markdown\" to get per-tool rows \
+         ready to paste into reports, spreadsheets or PR descriptions.\n\n\
          Note: Statistics are aggregated across all backend servers and include \
          both successful and failed calls."
     }
@@ -61,38 +89,81 @@ impl Tool for InspectUsageStatsTool {
         false
     }
 
-    async fn execute(&self, _args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
-        // Get connection ID from context
-        let connection_id = ctx.connection_id()
-            .ok_or_else(|| McpError::Other(anyhow::anyhow!("No connection ID available - usage stats require connection context")))?;
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        if args.scope != UsageScope::Connection && (args.connection_id.is_some() || args.all_connections) {
+            return Err(McpError::invalid_arguments("connection_id and all_connections require scope \"connection\""));
+        }
 
-        // Query kodegend daemon via IPC for aggregated usage statistics
-        let aggregated = get_usage_stats(connection_id)
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to query usage stats from kodegend: {}", e)))?;
+        let estimator = args.tokenizer.estimator();
+        let mut connections = Vec::new();
+        let (totals, instances) = match args.scope {
+            UsageScope::Connection => {
+                let targets = match (args.connection_id.as_deref(), args.all_connections) {
+                    (Some(_), true) => {
+                        return Err(McpError::invalid_arguments("connection_id and all_connections are mutually exclusive"));
+                    }
+                    (Some(connection_id), false) => vec![connection_id.to_string()],
+                    (None, true) => known_connections(&ctx).await?,
+                    (None, false) => Vec::new(),
+                };
 
-        // Aggregate statistics across all available servers
-        let mut total_calls = 0u64;
-        let mut successful_calls = 0u64;
-        let mut failed_calls = 0u64;
-        let mut tool_usage_map = std::collections::HashMap::new();
+                let requester = connection_id(&ctx)?;
+                if targets.is_empty() {
+                    (Self::connection_totals(requester, estimator.as_ref())?, Vec::new())
+                } else {
+                    // Reading other connections' statistics is subject to the access policy
+                    let policy = AccessPolicy::load()?;
+                    let mut totals = UsageTotals::default();
+                    for target in &targets {
+                        policy.authorize_connection(requester, target)?;
+                        let connection = match Self::connection_totals(target, estimator.as_ref()) {
+                            Ok(connection) => connection,
+                            // Connections listed from stats files may be gone
+                            Err(e) if args.all_connections && target != requester => {
+                                log::debug!("Skipping usage stats of connection {target}: {e}");
+                                continue;
+                            }
+                            Err(e) => return Err(e),
+                        };
+                        connections.push(ConnectionUsage {
+                            connection_id: target.clone(),
+                            total_calls: connection.total_calls,
+                            successful_calls: connection.successful_calls,
+                            failed_calls: connection.failed_calls,
+                            tools_used: connection.tool_counts.len(),
+                        });
+                        totals.absorb(connection);
+                    }
+                    (totals, Vec::new())
+                }
+            }
+            UsageScope::Instance => {
+                let instance_id = args.instance_id.as_deref()
+                    .ok_or_else(|| McpError::invalid_arguments("instance_id is required when scope is \"instance\""))?;
+                AccessPolicy::load()?.authorize_instance(instance_id)?;
 
-        for server in &aggregated.servers {
-            // Only process servers that responded successfully
-            if server.available {
-                total_calls += server.stats.total_tool_calls;
-                successful_calls += server.stats.successful_calls;
-                failed_calls += server.stats.failed_calls;
+                let instance = UsageTracker::load_instance(instance_id).await
+                    .ok_or_else(|| McpError::resource_not_found(format!("No stats file found for instance: {instance_id}")))?;
 
-                // Aggregate per-tool counts (tool_counts is a HashMap<String, u64>)
-                for (tool_name, count) in &server.stats.tool_counts {
-                    *tool_usage_map.entry(tool_name.clone()).or_insert(0u64) += count;
-                }
+                let context_costs = Self::history_costs(&[instance_id], estimator.as_ref()).await;
+                (UsageTotals::from_stats(&instance.stats, context_costs), vec![instance])
             }
-        }
+            UsageScope::AllInstances => {
+                let policy = AccessPolicy::load()?;
+                policy.authorize_cross_connection("scope \"all_instances\"")?;
+                let instances = policy.visible_instances(UsageTracker::load_all_instances().await);
+                let merged = UsageTracker::aggregate_instances(&instances);
+                let instance_ids: Vec<&str> = instances.iter().map(|instance| instance.instance_id.as_str()).collect();
+                let context_costs = Self::history_costs(&instance_ids, estimator.as_ref()).await;
+                (UsageTotals::from_stats(&merged, context_costs), instances)
+            }
+        };
+
+        let context_costs = rank_context_costs(&totals.context_costs);
 
         // Convert tool usage map to vector of ToolUsageStats
         // Note: We don't have duration data in the usage stats, only in history
-        let tool_usage: Vec<ToolUsageStats> = tool_usage_map
+        let tool_usage: Vec<ToolUsageStats> = totals.tool_counts
             .into_iter()
             .map(|(tool_name, call_count)| {
                 ToolUsageStats {
@@ -104,44 +175,263 @@ impl Tool for InspectUsageStatsTool {
             })
             .collect();
 
-        let success_rate = if total_calls > 0 {
-            (successful_calls as f64 / total_calls as f64) * 100.0
+        let success_rate = if totals.total_calls > 0 {
+            (totals.successful_calls as f64 / totals.total_calls as f64) * 100.0
         } else {
             0.0
         };
 
-        // Calculate session duration from first_used to last_used across all servers
-        let session_duration_ms = aggregated.servers
-            .iter()
-            .filter(|s| s.available)
-            .map(|s| {
-                let duration = s.stats.last_used.saturating_sub(s.stats.first_used);
-                duration.max(0) as u64
-            })
-            .max()
-            .unwrap_or(0);
-
-        // Terminal formatted summary
-        let summary = format!(
-            "\x1b[35mUsage Statistics\x1b[0m\n\
-             Total: {} · Success: {} · Failed: {} · Rate: {:.1}%",
-            total_calls,
-            successful_calls,
-            failed_calls,
+        // Summary in the requested style
+        let mut detail = format!(
+            "Total: {} · Success: {} · Failed: {} · Rate: {:.1}%",
+            totals.total_calls,
+            totals.successful_calls,
+            totals.failed_calls,
             success_rate
         );
+        if args.scope == UsageScope::AllInstances {
+            detail.push_str(&format!(" · Instances: {}", instances.len()));
+        }
+        if !connections.is_empty() {
+            detail.push_str(&format!(" · Connections: {}", connections.len()));
+        }
+        if let Some(top) = context_costs.first() {
+            detail.push_str(&format!(" · Top cost: {} (~{} tokens)", top.tool_name, top.estimated_tokens));
+        }
+        let summary = SummaryStyle::resolve(args.style).summary("Usage Statistics", &detail);
+
+        // Export formats replace the summary as display output
+        let display = match args.format {
+            UsageFormat::Json => summary,
+            UsageFormat::Jsonl => tool_rows(&tool_usage, &context_costs)
+                .iter()
+                .map(|row| serde_json::json!({
+                    "tool_name": row.tool_name,
+                    "calls": row.calls,
+                    "args_bytes": row.args_bytes,
+                    "output_bytes": row.output_bytes,
+                    "estimated_tokens": row.estimated_tokens,
+                }).to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            UsageFormat::Csv => usage_table(&tool_usage, &context_costs).to_csv(),
+            UsageFormat::Markdown => format!(
+                "**Total calls:** {} · **Successful:** {} · **Failed:** {} · **Success rate:** {:.1}%\n\n{}",
+                totals.total_calls,
+                totals.successful_calls,
+                totals.failed_calls,
+                success_rate,
+                usage_table(&tool_usage, &context_costs).to_markdown()
+            ),
+        };
+
+        let instances = instances
+            .into_iter()
+            .map(|instance| InstanceUsage {
+                instance_id: instance.instance_id,
+                total_calls: instance.stats.total_tool_calls,
+                successful_calls: instance.stats.successful_calls,
+                failed_calls: instance.stats.failed_calls,
+                tools_used: instance.stats.tool_counts.len(),
+                connections: instance.connections.len(),
+                total_sessions: instance.stats.total_sessions,
+                first_used: instance.stats.first_used,
+                last_used: instance.stats.last_used,
+            })
+            .collect();
 
         let output = InspectUsageOutput {
-            success: true,
-            total_calls: total_calls as usize,
-            tools_used: tool_usage.len(),
-            tool_usage,
-            session_duration_ms,
-            success_rate,
-            successful_calls: successful_calls as usize,
-            failed_calls: failed_calls as usize,
+            base: introspection::InspectUsageOutput {
+                success: true,
+                total_calls: totals.total_calls as usize,
+                tools_used: tool_usage.len(),
+                tool_usage,
+                session_duration_ms: totals.session_duration_ms,
+                success_rate,
+                successful_calls: totals.successful_calls as usize,
+                failed_calls: totals.failed_calls as usize,
+            },
+            scope: args.scope,
+            format: args.format,
+            instances,
+            connections,
+            context_costs,
+        };
+
+        // Pseudonymize machine details for shareable reports
+        let (display, output) = anonymize::report(args.anonymize, display, output)?;
+
+        Ok(ToolResponse::new(display, output))
+    }
+}
+
+// ============================================================================
+// AGGREGATION
+// ============================================================================
+
+/// Totals gathered from either kodegend or the stats files on disk
+#[derive(Default)]
+struct UsageTotals {
+    total_calls: u64,
+    successful_calls: u64,
+    failed_calls: u64,
+    tool_counts: HashMap<String, u64>,
+    session_duration_ms: u64,
+    context_costs: HashMap<String, ToolCost>,
+}
+
+impl UsageTotals {
+    /// Add another connection's totals; session duration is the longest of the two
+    fn absorb(&mut self, other: Self) {
+        self.total_calls += other.total_calls;
+        self.successful_calls += other.successful_calls;
+        self.failed_calls += other.failed_calls;
+        for (tool_name, count) in other.tool_counts {
+            *self.tool_counts.entry(tool_name).or_insert(0) += count;
+        }
+        self.session_duration_ms = self.session_duration_ms.max(other.session_duration_ms);
+        for (tool_name, cost) in other.context_costs {
+            self.context_costs.entry(tool_name).or_default().add(&cost);
+        }
+    }
+
+    fn from_stats(stats: &UsageStats, context_costs: HashMap<String, ToolCost>) -> Self {
+        Self {
+            total_calls: stats.total_tool_calls,
+            successful_calls: stats.successful_calls,
+            failed_calls: stats.failed_calls,
+            tool_counts: stats.tool_counts.clone(),
+            session_duration_ms: stats.last_used.saturating_sub(stats.first_used).max(0) as u64,
+            context_costs,
+        }
+    }
+}
+
+impl InspectUsageStatsTool {
+    /// Query kodegend for a connection's live statistics
+    ///
+    /// Callers check the access policy for `connection_id` first.
+    fn connection_totals(connection_id: &str, estimator: &dyn TokenEstimator) -> Result<UsageTotals, McpError> {
+        // Query kodegend daemon via IPC for aggregated usage statistics
+        let aggregated = get_usage_stats(connection_id)
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to query usage stats from kodegend: {}", e)))?;
+
+        // Aggregate statistics across all available servers
+        let mut totals = UsageTotals {
+            total_calls: 0,
+            successful_calls: 0,
+            failed_calls: 0,
+            tool_counts: HashMap::new(),
+            session_duration_ms: 0,
+            context_costs: HashMap::new(),
         };
 
-        Ok(ToolResponse::new(summary, output))
+        for server in &aggregated.servers {
+            // Only process servers that responded successfully
+            if server.available {
+                totals.total_calls += server.stats.total_tool_calls;
+                totals.successful_calls += server.stats.successful_calls;
+                totals.failed_calls += server.stats.failed_calls;
+
+                // Aggregate per-tool counts (tool_counts is a HashMap<String, u64>)
+                for (tool_name, count) in &server.stats.tool_counts {
+                    *totals.tool_counts.entry(tool_name.clone()).or_insert(0u64) += count;
+                }
+
+                // Session duration is the longest first_used..last_used span of any server
+                let duration = server.stats.last_used.saturating_sub(server.stats.first_used);
+                totals.session_duration_ms = totals.session_duration_ms.max(duration.max(0) as u64);
+            }
+        }
+
+        // kodegend only reports counters, so measure context costs from the recorded calls;
+        // the counters are still worth reporting when the history is unavailable
+        match fetch_history(connection_id) {
+            Ok(history) => {
+                for call in history.calls {
+                    let cost = ToolCost::of_call(&call.record.args_json, &call.record.output_json, estimator);
+                    totals.context_costs.entry(call.record.tool_name).or_default().add(&cost);
+                }
+            }
+            Err(e) => log::warn!("Context costs unavailable for connection {connection_id}: {e}"),
+        }
+
+        Ok(totals)
     }
+
+    /// Context costs measured from the persisted tool history of the given instances
+    async fn history_costs(instance_ids: &[&str], estimator: &dyn TokenEstimator) -> HashMap<String, ToolCost> {
+        let histories = load_disk_history().await;
+        let records = histories
+            .iter()
+            .filter(|history| instance_ids.contains(&history.instance_id.as_str()))
+            .flat_map(|history| &history.calls);
+        tool_costs(records, estimator)
+    }
+}
+
+/// Per-tool export row: call count joined with context cost
+struct ToolRow<'a> {
+    tool_name: &'a str,
+    calls: usize,
+    args_bytes: u64,
+    output_bytes: u64,
+    estimated_tokens: u64,
+}
+
+/// Export rows, most called tool first
+fn tool_rows<'a>(tool_usage: &'a [ToolUsageStats], context_costs: &[ToolContextCost]) -> Vec<ToolRow<'a>> {
+    let mut rows: Vec<ToolRow<'a>> = tool_usage
+        .iter()
+        .map(|usage| {
+            let cost = context_costs.iter().find(|cost| cost.tool_name == usage.tool_name);
+            ToolRow {
+                tool_name: &usage.tool_name,
+                calls: usage.call_count,
+                args_bytes: cost.map_or(0, |c| c.args_bytes),
+                output_bytes: cost.map_or(0, |c| c.output_bytes),
+                estimated_tokens: cost.map_or(0, |c| c.estimated_tokens),
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.tool_name.cmp(b.tool_name)));
+    rows
+}
+
+fn usage_table(tool_usage: &[ToolUsageStats], context_costs: &[ToolContextCost]) -> Table {
+    let mut table = Table::new(&["tool_name", "calls", "args_bytes", "output_bytes", "estimated_tokens"]);
+    for row in tool_rows(tool_usage, context_costs) {
+        table.push(vec![
+            row.tool_name.to_string(),
+            row.calls.to_string(),
+            row.args_bytes.to_string(),
+            row.output_bytes.to_string(),
+            row.estimated_tokens.to_string(),
+        ]);
+    }
+    table
+}
+
+/// Rank tools by estimated tokens, most expensive first
+fn rank_context_costs(costs: &HashMap<String, ToolCost>) -> Vec<ToolContextCost> {
+    let total_tokens: u64 = costs.values().map(|cost| cost.estimated_tokens).sum();
+
+    let mut ranked: Vec<ToolContextCost> = costs
+        .iter()
+        .map(|(tool_name, cost)| ToolContextCost {
+            tool_name: tool_name.clone(),
+            calls: cost.calls,
+            args_bytes: cost.args_bytes,
+            output_bytes: cost.output_bytes,
+            estimated_tokens: cost.estimated_tokens,
+            avg_tokens_per_call: cost.estimated_tokens.checked_div(cost.calls).unwrap_or(0),
+            share: if total_tokens > 0 {
+                cost.estimated_tokens as f64 / total_tokens as f64 * 100.0
+            } else {
+                0.0
+            },
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.estimated_tokens.cmp(&a.estimated_tokens).then_with(|| a.tool_name.cmp(&b.tool_name)));
+    ranked
 }
@@ -0,0 +1,237 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use crate::history::{connection_id, fetch_history, parse_timestamp};
+use crate::render::SummaryStyle;
+use crate::schema::inspect_workflows::{
+    InspectWorkflowsArgs, InspectWorkflowsOutput, WorkflowNode, WorkflowSequence,
+    WorkflowTransition, INSPECT_WORKFLOWS,
+};
+use crate::sessions;
+
+/// Minimum outgoing transitions before a source tool's rare edges are reported
+const RARE_MIN_OUTGOING: u64 = 5;
+
+/// Transitions taken at most this share of the time are considered rare
+const RARE_MAX_PROBABILITY: f64 = 0.1;
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct InspectWorkflowsTool;
+
+impl InspectWorkflowsTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for InspectWorkflowsTool {
+    type Args = InspectWorkflowsArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        INSPECT_WORKFLOWS
+    }
+
+    fn description() -> &'static str {
+        "Mine tool call history for the typical shape of agent workflows.\n\n\
+         Returns per-tool call counts, a tool-to-tool transition matrix with \
+         probabilities, the most frequent call sequences of sequence_length tools \
+         (default: 3), and rare transitions that deviate from the usual path. \
+         Transitions never cross an inactivity gap longer than timeout_minutes \
+         (default: 30). Set dot: true to also get the graph as Graphviz DOT.\n\n\
+         Useful for:\n\
+         - Understanding how an agent typically works through a task\n\
+         - Spotting unusual or unexpected tool paths\n\
+         - Visualizing workflows with `dot -Tsvg`"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        if !(2..=6).contains(&args.sequence_length) {
+            return Err(McpError::invalid_arguments("sequence_length must be between 2 and 6"));
+        }
+        if args.timeout_minutes == 0 {
+            return Err(McpError::invalid_arguments("timeout_minutes must be greater than 0"));
+        }
+
+        let history = fetch_history(connection_id(&ctx)?)?;
+
+        // History is chronological, so a since filter is a suffix
+        let start = match args.since.as_deref() {
+            Some(since) => {
+                let since = parse_timestamp(since)
+                    .ok_or_else(|| McpError::invalid_arguments(format!("Invalid since timestamp: {since}")))?;
+                history.calls
+                    .iter()
+                    .position(|call| call.time.is_some_and(|time| time >= since))
+                    .unwrap_or(history.calls.len())
+            }
+            None => 0,
+        };
+        let calls = &history.calls[start..];
+
+        let timeout = Duration::from_secs(args.timeout_minutes.saturating_mul(60));
+        let segments = sessions::segment(calls, timeout);
+
+        let mut node_counts: HashMap<&str, u64> = HashMap::new();
+        let mut edge_counts: HashMap<(&str, &str), u64> = HashMap::new();
+        let mut sequence_counts: HashMap<Vec<&str>, u64> = HashMap::new();
+
+        for (_, range) in &segments {
+            let names: Vec<&str> = calls[range.clone()]
+                .iter()
+                .map(|call| call.record.tool_name.as_str())
+                .collect();
+
+            for name in &names {
+                *node_counts.entry(name).or_insert(0) += 1;
+            }
+            for pair in names.windows(2) {
+                *edge_counts.entry((pair[0], pair[1])).or_insert(0) += 1;
+            }
+            for window in names.windows(args.sequence_length) {
+                *sequence_counts.entry(window.to_vec()).or_insert(0) += 1;
+            }
+        }
+
+        let mut outgoing: HashMap<&str, u64> = HashMap::new();
+        for ((from, _), count) in &edge_counts {
+            *outgoing.entry(from).or_insert(0) += count;
+        }
+
+        let mut tools: Vec<WorkflowNode> = node_counts
+            .into_iter()
+            .map(|(tool_name, calls)| WorkflowNode { tool_name: tool_name.to_string(), calls })
+            .collect();
+        tools.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.tool_name.cmp(&b.tool_name)));
+
+        let mut transitions: Vec<WorkflowTransition> = edge_counts
+            .into_iter()
+            .map(|((from, to), count)| WorkflowTransition {
+                from: from.to_string(),
+                to: to.to_string(),
+                count,
+                probability: count as f64 / outgoing[from] as f64,
+            })
+            .collect();
+        transitions.sort_by(|a, b| {
+            b.count.cmp(&a.count)
+                .then_with(|| a.from.cmp(&b.from))
+                .then_with(|| a.to.cmp(&b.to))
+        });
+
+        // Only repeated sequences describe a workflow
+        let mut sequences: Vec<WorkflowSequence> = sequence_counts
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(tools, count)| WorkflowSequence {
+                tools: tools.into_iter().map(str::to_string).collect(),
+                count,
+            })
+            .collect();
+        sequences.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tools.cmp(&b.tools)));
+        sequences.truncate(args.max_results);
+
+        let mut rare_transitions: Vec<WorkflowTransition> = transitions
+            .iter()
+            .filter(|t| outgoing[t.from.as_str()] >= RARE_MIN_OUTGOING && t.probability <= RARE_MAX_PROBABILITY)
+            .cloned()
+            .collect();
+        rare_transitions.sort_by(|a, b| a.probability.total_cmp(&b.probability));
+        rare_transitions.truncate(args.max_results);
+
+        let dot = args.dot.then(|| render_dot(&tools, &transitions));
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let summary = style.summary(
+            "Tool Workflows",
+            &format!(
+                "Calls: {} · Tools: {} · Transitions: {} · Rare: {}",
+                calls.len(),
+                tools.len(),
+                transitions.len(),
+                rare_transitions.len()
+            ),
+        );
+
+        let output = InspectWorkflowsOutput {
+            success: true,
+            calls_scanned: calls.len(),
+            sessions: segments.len(),
+            tools,
+            transitions,
+            sequences,
+            rare_transitions,
+            dot,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
+
+// ============================================================================
+// GRAPHVIZ EXPORT
+// ============================================================================
+
+/// Render the transition graph as a Graphviz digraph
+fn render_dot(tools: &[WorkflowNode], transitions: &[WorkflowTransition]) -> String {
+    let max_count = transitions.iter().map(|t| t.count).max().unwrap_or(1) as f64;
+
+    let mut dot = String::from("digraph workflows {\n  rankdir=LR;\n  node [shape=box];\n");
+    for node in tools {
+        let _ = writeln!(
+            dot,
+            "  \"{}\" [label=\"{}\\n{} calls\"];",
+            escape(&node.tool_name),
+            escape(&node.tool_name),
+            node.calls
+        );
+    }
+    for t in transitions {
+        let _ = writeln!(
+            dot,
+            "  \"{}\" -> \"{}\" [label=\"{} ({:.0}%)\", penwidth={:.1}];",
+            escape(&t.from),
+            escape(&t.to),
+            t.count,
+            t.probability * 100.0,
+            1.0 + 4.0 * t.count as f64 / max_count
+        );
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Escape a string for use inside a quoted DOT identifier
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
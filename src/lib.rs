@@ -3,12 +3,58 @@
 //! This module provides tools for understanding how tools are being used,
 //! viewing execution history, and analyzing usage patterns.
 
+pub mod access;
+pub mod activity;
+pub mod alerts;
+pub mod anomaly;
+pub mod anonymize;
+mod await_tool_call;
+pub mod budget;
+mod check_budget;
+mod compare_usage;
+pub mod context_cost;
+pub mod export;
+mod export_session;
+pub mod history;
+mod inspect_alerts;
+mod inspect_anomalies;
+mod inspect_budget;
+mod inspect_commands;
+mod inspect_loops;
+mod inspect_session_summary;
+mod inspect_sessions;
 mod inspect_tool_calls;
+mod inspect_touched_files;
 mod inspect_usage_stats;
+mod inspect_workflows;
+pub mod metrics;
+pub mod otlp;
+pub mod redact;
+pub mod render;
+mod replay_tool_calls;
+pub mod schema;
+pub mod sessions;
+pub mod stream;
 pub mod usage_tracker;
+mod watch_tool_calls;
 
+pub use await_tool_call::AwaitToolCallTool;
+pub use check_budget::CheckBudgetTool;
+pub use compare_usage::CompareUsageTool;
+pub use export_session::ExportSessionTool;
+pub use inspect_alerts::InspectAlertsTool;
+pub use inspect_anomalies::InspectAnomaliesTool;
+pub use inspect_budget::InspectBudgetTool;
+pub use inspect_commands::InspectCommandsTool;
+pub use inspect_loops::InspectLoopsTool;
+pub use inspect_session_summary::InspectSessionSummaryTool;
+pub use inspect_sessions::InspectSessionsTool;
 pub use inspect_tool_calls::InspectToolCallsTool;
+pub use inspect_touched_files::InspectTouchedFilesTool;
 pub use inspect_usage_stats::InspectUsageStatsTool;
+pub use inspect_workflows::InspectWorkflowsTool;
+pub use replay_tool_calls::ReplayToolCallsTool;
+pub use watch_tool_calls::WatchToolCallsTool;
 
 /// Start the introspection HTTP server programmatically
 ///
@@ -55,18 +101,67 @@ pub async fn start_server(
 pub async fn start_server_with_listener(
     listener: tokio::net::TcpListener,
     tls_config: Option<(std::path::PathBuf, std::path::PathBuf)>,
+) -> anyhow::Result<kodegen_server_http::ServerHandle> {
+    start_server_with_metrics(listener, tls_config, None).await
+}
+
+/// Start introspection HTTP server with a Prometheus `/metrics` endpoint
+///
+/// Like [`start_server_with_listener`], plus the metrics endpoint served on
+/// its own pre-bound listener. Without `metrics_listener` the endpoint is
+/// started from `KODEGEN_INTROSPECTION_METRICS_ADDR` when that is set, as in
+/// the standalone binary. The tool call stream (`KODEGEN_INTROSPECTION_STREAM_ADDR`),
+/// the OpenTelemetry exporter (`OTEL_EXPORTER_OTLP_ENDPOINT`) and the alert
+/// rule evaluator are started as well. All of them stop when the server
+/// shuts down.
+///
+/// # Arguments
+/// * `listener` - Pre-bound TcpListener for the MCP server
+/// * `tls_config` - Optional (cert_path, key_path) for HTTPS
+/// * `metrics_listener` - Optional pre-bound TcpListener for `/metrics`
+///
+/// # Returns
+/// ServerHandle for graceful shutdown, or error if startup fails
+pub async fn start_server_with_metrics(
+    listener: tokio::net::TcpListener,
+    tls_config: Option<(std::path::PathBuf, std::path::PathBuf)>,
+    metrics_listener: Option<tokio::net::TcpListener>,
 ) -> anyhow::Result<kodegen_server_http::ServerHandle> {
     use kodegen_server_http::{ServerBuilder, Managers, RouterSet, register_tool};
     use rmcp::handler::server::router::{prompt::PromptRouter, tool::ToolRouter};
 
     let mut builder = ServerBuilder::new()
         .category(kodegen_config::CATEGORY_INTROSPECTION)
-        .register_tools(|| async {
+        .register_tools(move || async move {
             let mut tool_router = ToolRouter::new();
             let mut prompt_router = PromptRouter::new();
             let managers = Managers::new();
 
-            // Register all 2 introspection tools
+            // Prometheus endpoint, stopped with the other managers
+            let metrics = match metrics_listener {
+                Some(listener) => Some(crate::metrics::start_metrics_server(listener).await?),
+                None => crate::metrics::start_from_env().await?,
+            };
+            if let Some(metrics) = metrics {
+                managers.register(metrics.into_shutdown_hook()).await;
+            }
+
+            // Live tool call stream, enabled by KODEGEN_INTROSPECTION_STREAM_ADDR
+            if let Some(stream) = crate::stream::start_from_env().await? {
+                managers.register(stream.into_shutdown_hook()).await;
+            }
+
+            // OpenTelemetry export, enabled by OTEL_EXPORTER_OTLP_ENDPOINT
+            if let Some(otlp) = crate::otlp::start_from_env()? {
+                managers.register(otlp.into_shutdown_hook()).await;
+            }
+
+            // Alert rule evaluation, enabled by rules in introspection-alerts.json
+            if let Some(alerts) = crate::alerts::start_from_config()? {
+                managers.register(alerts.into_shutdown_hook()).await;
+            }
+
+            // Register all 16 introspection tools
             (tool_router, prompt_router) = register_tool(
                 tool_router,
                 prompt_router,
@@ -79,6 +174,96 @@ pub async fn start_server_with_listener(
                 crate::InspectToolCallsTool::new(),
             );
 
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::InspectSessionsTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::InspectSessionSummaryTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::InspectTouchedFilesTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::InspectCommandsTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::InspectLoopsTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::InspectWorkflowsTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::ExportSessionTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::ReplayToolCallsTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::WatchToolCallsTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::AwaitToolCallTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::InspectAlertsTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::InspectBudgetTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::CheckBudgetTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::InspectAnomaliesTool::new(),
+            );
+
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::CompareUsageTool::new(),
+            );
+
             Ok(RouterSet::new(tool_router, prompt_router, managers))
         })
         .with_listener(listener);
@@ -9,14 +9,26 @@ use rmcp::handler::server::router::{prompt::PromptRouter, tool::ToolRouter};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    ServerBuilder::new()
+    // Optional Prometheus endpoint, enabled by KODEGEN_INTROSPECTION_METRICS_ADDR
+    let metrics = kodegen_tools_introspection::metrics::start_from_env().await?;
+
+    // Optional live tool call stream, enabled by KODEGEN_INTROSPECTION_STREAM_ADDR
+    let stream = kodegen_tools_introspection::stream::start_from_env().await?;
+
+    // Optional OpenTelemetry export, enabled by OTEL_EXPORTER_OTLP_ENDPOINT
+    let otlp = kodegen_tools_introspection::otlp::start_from_env()?;
+
+    // Alert rule evaluation, enabled by rules in introspection-alerts.json
+    let alerts = kodegen_tools_introspection::alerts::start_from_config()?;
+
+    let result = ServerBuilder::new()
         .category(CATEGORY_INTROSPECTION)
         .register_tools(|| async {
             let tool_router = ToolRouter::new();
             let prompt_router = PromptRouter::new();
             let managers = Managers::new();
 
-            // Register all 2 introspection tools
+            // Register all 16 introspection tools
             let (tool_router, prompt_router) = register_tool(
                 tool_router,
                 prompt_router,
@@ -29,8 +41,114 @@ async fn main() -> Result<()> {
                 kodegen_tools_introspection::InspectToolCallsTool::new(),
             );
 
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::InspectSessionsTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::InspectSessionSummaryTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::InspectTouchedFilesTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::InspectCommandsTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::InspectLoopsTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::InspectWorkflowsTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::ExportSessionTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::ReplayToolCallsTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::WatchToolCallsTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::AwaitToolCallTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::InspectAlertsTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::InspectBudgetTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::CheckBudgetTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::InspectAnomaliesTool::new(),
+            );
+
+            let (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                kodegen_tools_introspection::CompareUsageTool::new(),
+            );
+
             Ok(RouterSet::new(tool_router, prompt_router, managers))
         })
         .run()
-        .await
+        .await;
+
+    // Final telemetry export, then stop the background tasks and side endpoints
+    if let Some(otlp) = otlp {
+        otlp.shutdown().await;
+    }
+    if let Some(alerts) = alerts {
+        alerts.shutdown().await;
+    }
+    if let Some(metrics) = metrics {
+        metrics.shutdown().await;
+    }
+    if let Some(stream) = stream {
+        stream.shutdown().await;
+    }
+
+    result
 }
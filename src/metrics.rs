@@ -0,0 +1,373 @@
+//! Prometheus `/metrics` endpoint
+//!
+//! `kodegen_server_http` owns the MCP router, so the endpoint runs on its own
+//! listener. Each scrape reads the stats files written by every instance's
+//! `UsageTracker`, the tool history appended since the previous scrape (for
+//! latency histograms and context costs kept in memory) and the service table from kodegend
+//! (for per-server availability).
+
+use axum::Router;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use kodegen_mcp_schema::introspection::ToolCallRecord;
+use kodegen_server_http::ShutdownHook;
+use kodegend_client_ipc::{get_all_services, ServiceStateKind};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::LazyLock;
+
+use crate::context_cost::{CharRatioEstimator, ToolCost};
+use crate::history::history_instance_id;
+use crate::usage_tracker::UsageTracker;
+
+/// Environment variable holding the address to serve `/metrics` on (unset: disabled)
+pub const METRICS_ADDR_ENV: &str = "KODEGEN_INTROSPECTION_METRICS_ADDR";
+
+/// Upper bounds (seconds) of the tool latency histogram buckets
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+/// Handle to a running metrics endpoint
+pub struct MetricsHandle {
+    local_addr: SocketAddr,
+    shutdown: tokio::sync::oneshot::Sender<()>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MetricsHandle {
+    /// Address the endpoint is listening on
+    #[must_use]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stop accepting scrapes and wait for in-flight requests to finish
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(());
+        let _ = self.task.await;
+    }
+
+    /// Hook that stops the endpoint when the MCP server shuts down its managers
+    #[must_use]
+    pub fn into_shutdown_hook(self) -> MetricsShutdown {
+        MetricsShutdown(tokio::sync::Mutex::new(Some(self)))
+    }
+}
+
+/// [`ShutdownHook`] owning a running metrics endpoint
+pub struct MetricsShutdown(tokio::sync::Mutex<Option<MetricsHandle>>);
+
+impl ShutdownHook for MetricsShutdown {
+    fn shutdown(&self) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + '_>> {
+        Box::pin(async move {
+            if let Some(handle) = self.0.lock().await.take() {
+                handle.shutdown().await;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Serve `/metrics` on a pre-bound listener (non-blocking)
+pub async fn start_metrics_server(listener: tokio::net::TcpListener) -> anyhow::Result<MetricsHandle> {
+    let local_addr = listener.local_addr()
+        .map_err(|e| anyhow::anyhow!("Failed to get metrics listener address: {}", e))?;
+
+    let router = Router::new().route("/metrics", get(metrics_handler));
+    let (shutdown, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+
+    let task = tokio::spawn(async move {
+        let server = axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                let _ = shutdown_rx.await;
+            });
+        if let Err(e) = server.await {
+            log::error!("Metrics endpoint failed: {e}");
+        }
+    });
+
+    log::info!("Prometheus metrics available on http://{local_addr}/metrics");
+
+    Ok(MetricsHandle { local_addr, shutdown, task })
+}
+
+/// Start the metrics endpoint if `KODEGEN_INTROSPECTION_METRICS_ADDR` is set
+pub async fn start_from_env() -> anyhow::Result<Option<MetricsHandle>> {
+    let Ok(addr) = std::env::var(METRICS_ADDR_ENV) else {
+        return Ok(None);
+    };
+
+    let addr: SocketAddr = addr.parse()
+        .map_err(|e| anyhow::anyhow!("Invalid {METRICS_ADDR_ENV} '{addr}': {e}"))?;
+    let listener = tokio::net::TcpListener::bind(addr).await
+        .map_err(|e| anyhow::anyhow!("Failed to bind metrics endpoint to {}: {}", addr, e))?;
+
+    start_metrics_server(listener).await.map(Some)
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+        render_metrics().await,
+    )
+}
+
+/// Render all metrics in Prometheus text exposition format
+pub async fn render_metrics() -> String {
+    let mut out = String::new();
+
+    render_usage(&mut out).await;
+    render_history(&mut out).await;
+
+    // IPC to kodegend is blocking
+    match tokio::task::spawn_blocking(get_all_services).await {
+        Ok(Ok(services)) => {
+            header(&mut out, "kodegen_daemon_up", "gauge", "Whether kodegend answered the status query");
+            out.push_str("kodegen_daemon_up 1\n");
+
+            header(&mut out, "kodegen_server_up", "gauge", "Whether the backend server is running");
+            for service in &services {
+                let up = u8::from(matches!(service.state, ServiceStateKind::Running));
+                let _ = writeln!(out, "kodegen_server_up{{server=\"{}\"}} {up}", escape(&service.name));
+            }
+
+            header(&mut out, "kodegen_server_restarts_total", "counter", "Backend server restarts");
+            for service in &services {
+                let _ = writeln!(out, "kodegen_server_restarts_total{{server=\"{}\"}} {}", escape(&service.name), service.restart_count);
+            }
+
+            header(&mut out, "kodegen_server_uptime_seconds", "gauge", "Backend server uptime");
+            for service in &services {
+                if let Some(uptime) = service.uptime {
+                    let _ = writeln!(out, "kodegen_server_uptime_seconds{{server=\"{}\"}} {}", escape(&service.name), uptime.as_secs_f64());
+                }
+            }
+        }
+        Ok(Err(e)) => {
+            log::debug!("kodegend unavailable for metrics scrape: {e}");
+            header(&mut out, "kodegen_daemon_up", "gauge", "Whether kodegend answered the status query");
+            out.push_str("kodegen_daemon_up 0\n");
+        }
+        Err(e) => log::error!("Service status query panicked: {e}"),
+    }
+
+    out
+}
+
+/// `UsageTracker` counters from every instance's stats file
+async fn render_usage(out: &mut String) {
+    let instances = UsageTracker::load_all_instances().await;
+
+    header(out, "kodegen_tool_calls_total", "counter", "Tool calls per instance and tool");
+    for instance in &instances {
+        let mut tools: Vec<_> = instance.stats.tool_counts.iter().collect();
+        tools.sort();
+        for (tool_name, count) in tools {
+            let _ = writeln!(
+                out,
+                "kodegen_tool_calls_total{{instance=\"{}\",tool=\"{}\"}} {count}",
+                escape(&instance.instance_id),
+                escape(tool_name)
+            );
+        }
+    }
+
+    header(out, "kodegen_tool_call_results_total", "counter", "Tool calls per instance by result");
+    for instance in &instances {
+        let id = escape(&instance.instance_id);
+        let _ = writeln!(out, "kodegen_tool_call_results_total{{instance=\"{id}\",result=\"success\"}} {}", instance.stats.successful_calls);
+        let _ = writeln!(out, "kodegen_tool_call_results_total{{instance=\"{id}\",result=\"failure\"}} {}", instance.stats.failed_calls);
+    }
+
+    header(out, "kodegen_sessions_total", "counter", "Sessions started per instance");
+    for instance in &instances {
+        let _ = writeln!(out, "kodegen_sessions_total{{instance=\"{}\"}} {}", escape(&instance.instance_id), instance.stats.total_sessions);
+    }
+
+    header(out, "kodegen_last_used_timestamp_seconds", "gauge", "Unix time of the instance's last tool call");
+    for instance in &instances {
+        let _ = writeln!(out, "kodegen_last_used_timestamp_seconds{{instance=\"{}\"}} {}", escape(&instance.instance_id), instance.stats.last_used);
+    }
+}
+
+/// Latency histograms and context costs fed from the persisted tool history
+async fn render_history(out: &mut String) {
+    let mut counters = HISTORY.lock().await;
+    counters.update().await;
+
+    header(out, "kodegen_tool_duration_seconds", "histogram", "Tool call latency over the persisted tool history");
+    for (tool_name, histogram) in &counters.histograms {
+        let tool = escape(tool_name);
+        for (bucket, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+            let _ = writeln!(out, "kodegen_tool_duration_seconds_bucket{{tool=\"{tool}\",le=\"{bound}\"}} {bucket}");
+        }
+        let _ = writeln!(out, "kodegen_tool_duration_seconds_bucket{{tool=\"{tool}\",le=\"+Inf\"}} {}", histogram.count);
+        let _ = writeln!(out, "kodegen_tool_duration_seconds_sum{{tool=\"{tool}\"}} {}", histogram.sum);
+        let _ = writeln!(out, "kodegen_tool_duration_seconds_count{{tool=\"{tool}\"}} {}", histogram.count);
+    }
+
+    header(out, "kodegen_tool_context_bytes_total", "counter", "Serialized argument and output bytes per tool");
+    for (instance_id, costs) in &counters.costs {
+        for (tool_name, cost) in costs {
+            for (direction, bytes) in [("args", cost.args_bytes), ("output", cost.output_bytes)] {
+                let _ = writeln!(
+                    out,
+                    "kodegen_tool_context_bytes_total{{instance=\"{}\",tool=\"{}\",direction=\"{direction}\"}} {bytes}",
+                    escape(instance_id),
+                    escape(tool_name)
+                );
+            }
+        }
+    }
+
+    header(out, "kodegen_tool_context_tokens_total", "counter", "Estimated context tokens per tool");
+    for (instance_id, costs) in &counters.costs {
+        for (tool_name, cost) in costs {
+            let _ = writeln!(
+                out,
+                "kodegen_tool_context_tokens_total{{instance=\"{}\",tool=\"{}\"}} {}",
+                escape(instance_id),
+                escape(tool_name),
+                cost.estimated_tokens
+            );
+        }
+    }
+}
+
+// ============================================================================
+// HISTORY COUNTERS
+// ============================================================================
+
+/// Process-wide history counters, shared by every scrape
+static HISTORY: LazyLock<tokio::sync::Mutex<HistoryCounters>> = LazyLock::new(Default::default);
+
+#[derive(Default)]
+struct Histogram {
+    buckets: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; LATENCY_BUCKETS.len()];
+        }
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+/// How far one instance's history file has been counted
+#[derive(Default)]
+struct FileCursor {
+    /// Bytes of complete lines already read
+    offset: u64,
+    /// Timestamp of the newest counted call
+    watermark: Option<String>,
+}
+
+/// Monotonic histograms and context costs that only ever add calls
+///
+/// Files are read from the last offset, so a scrape only parses what was
+/// appended since the previous one. A file shorter than the offset was
+/// rotated and is read again from the start, skipping calls up to the
+/// watermark so they are not counted twice.
+#[derive(Default)]
+struct HistoryCounters {
+    histograms: BTreeMap<String, Histogram>,
+    /// Context cost per instance and tool, estimated with the default heuristic
+    costs: BTreeMap<String, BTreeMap<String, ToolCost>>,
+    files: HashMap<String, FileCursor>,
+}
+
+impl HistoryCounters {
+    async fn update(&mut self) {
+        let Ok(log_dir) = kodegen_config::KodegenConfig::log_dir() else {
+            return;
+        };
+        let Ok(mut entries) = tokio::fs::read_dir(log_dir).await else {
+            return;
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let file_name = entry.file_name();
+            let Some(instance_id) = file_name.to_str().and_then(history_instance_id) else {
+                continue;
+            };
+            let cursor = self.files.entry(instance_id.to_string()).or_default();
+            match read_appended(&entry.path(), cursor).await {
+                Ok((calls, rotated)) => {
+                    // After a rotation the file starts with calls counted before
+                    let counted = if rotated { cursor.watermark.clone() } else { None };
+                    for call in calls {
+                        if counted.as_ref().is_some_and(|seen| call.timestamp <= *seen) {
+                            continue;
+                        }
+                        if cursor.watermark.as_ref().is_none_or(|seen| call.timestamp > *seen) {
+                            cursor.watermark = Some(call.timestamp.clone());
+                        }
+                        let cost = ToolCost::of_call(&call.args_json, &call.output_json, &CharRatioEstimator::default());
+                        self.costs
+                            .entry(instance_id.to_string())
+                            .or_default()
+                            .entry(call.tool_name.clone())
+                            .or_default()
+                            .add(&cost);
+                        if let Some(duration_ms) = call.duration_ms {
+                            self.histograms.entry(call.tool_name).or_default().observe(duration_ms as f64 / 1000.0);
+                        }
+                    }
+                }
+                Err(e) => log::warn!("Failed to read tool history {}: {}", entry.path().display(), e),
+            }
+        }
+    }
+}
+
+/// Complete lines appended to a history file since `cursor.offset`, and whether it was rotated
+async fn read_appended(path: &std::path::Path, cursor: &mut FileCursor) -> std::io::Result<(Vec<ToolCallRecord>, bool)> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let mut file = tokio::fs::File::open(path).await?;
+    let len = file.metadata().await?.len();
+    let rotated = len < cursor.offset;
+    if rotated {
+        cursor.offset = 0;
+    }
+    if len == cursor.offset {
+        return Ok((Vec::new(), rotated));
+    }
+
+    file.seek(std::io::SeekFrom::Start(cursor.offset)).await?;
+    let mut appended = Vec::new();
+    file.read_to_end(&mut appended).await?;
+
+    // A trailing partial line is still being written; read it next time
+    let complete = appended.iter().rposition(|byte| *byte == b'\n').map_or(0, |pos| pos + 1);
+    cursor.offset += complete as u64;
+
+    let calls = String::from_utf8_lossy(&appended[..complete])
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    Ok((calls, rotated))
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// Escape a label value per the text exposition format
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
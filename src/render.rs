@@ -0,0 +1,56 @@
+//! Rendering layer for human-readable summaries
+//!
+//! Every tool's display output starts with a two-line summary: a title and a
+//! detail line. Terminals get the title in magenta, other MCP clients and
+//! logs get plain text or Markdown. The MCP context does not expose client
+//! capabilities to tools, so the default style is configured per process and
+//! each call can override it.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Environment variable selecting the default style ("ansi", "plain" or "markdown")
+pub const STYLE_ENV: &str = "KODEGEN_INTROSPECTION_STYLE";
+
+/// How summaries are decorated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryStyle {
+    /// Magenta title via ANSI escapes, for terminals
+    Ansi,
+    /// No decoration
+    Plain,
+    /// Bold Markdown title
+    Markdown,
+}
+
+impl SummaryStyle {
+    /// Process-wide default: `KODEGEN_INTROSPECTION_STYLE`, else plain when
+    /// `NO_COLOR` is set, else ANSI
+    #[must_use]
+    pub fn from_env() -> Self {
+        match std::env::var(STYLE_ENV).as_deref() {
+            Ok("ansi") => Self::Ansi,
+            Ok("plain") => Self::Plain,
+            Ok("markdown") => Self::Markdown,
+            _ if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) => Self::Plain,
+            _ => Self::Ansi,
+        }
+    }
+
+    /// The requested style, falling back to the process default
+    #[must_use]
+    pub fn resolve(requested: Option<Self>) -> Self {
+        requested.unwrap_or_else(Self::from_env)
+    }
+
+    /// Render a title line followed by a detail line
+    #[must_use]
+    pub fn summary(self, title: &str, detail: &str) -> String {
+        match self {
+            Self::Ansi => format!("\x1b[35m{title}\x1b[0m\n{detail}"),
+            Self::Plain => format!("{title}\n{detail}"),
+            Self::Markdown => format!("**{title}**\n\n{detail}"),
+        }
+    }
+}
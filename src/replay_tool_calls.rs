@@ -0,0 +1,372 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use rmcp::ServiceExt;
+use rmcp::model::{CallToolRequestParam, ToolAnnotations};
+use rmcp::service::{RoleClient, RunningService};
+use rmcp::transport::StreamableHttpClientTransport;
+use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::time::Instant;
+
+use crate::export_session::{parse_bound, replay_step};
+use crate::history::{connection_id, fetch_history, is_failure_output};
+use crate::redact::Redactor;
+use crate::render::SummaryStyle;
+use crate::schema::export_session::ReplayStep;
+use crate::schema::replay_tool_calls::{
+    OutputDifference, ReplayResult, ReplayStatus, ReplayToolCallsArgs, ReplayToolCallsOutput,
+    REPLAY_TOOL_CALLS,
+};
+
+/// MCP client session with one backend server and the annotations of its tools
+struct Backend {
+    client: RunningService<RoleClient, ()>,
+    annotations: HashMap<String, Option<ToolAnnotations>>,
+}
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct ReplayToolCallsTool;
+
+impl ReplayToolCallsTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for ReplayToolCallsTool {
+    type Args = ReplayToolCallsArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        REPLAY_TOOL_CALLS
+    }
+
+    fn description() -> &'static str {
+        "Re-execute recorded tool calls against the backend servers and diff the \
+         new outputs against the recorded ones to detect environment drift.\n\n\
+         Steps come from a script produced by export_session, or from the most \
+         recent history calls (filter with tool_name/since, limit with max_calls). \
+         Only tools that the backend declares read-only and non-destructive are \
+         executed; everything else is refused. Differences are reported as JSON \
+         Pointer paths; use ignore_keys to skip volatile fields.\n\n\
+         Useful for:\n\
+         - Checking whether files, repos or services changed since a run\n\
+         - Verifying an exported session still reproduces\n\
+         - Spotting non-deterministic tools"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    // Replayed read-only tools may reach outside the local machine
+    fn open_world() -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        let history = fetch_history(connection_id(&ctx)?)?;
+
+        let steps: Vec<ReplayStep> = match args.script {
+            Some(script) => script.steps,
+            None => {
+                let since = parse_bound(args.since.as_deref(), "since")?;
+                let selected: Vec<_> = history.calls
+                    .iter()
+                    .filter(|call| args.tool_name.as_ref().is_none_or(|name| &call.record.tool_name == name))
+                    .filter(|call| match (since, call.time) {
+                        (Some(since), Some(time)) => time >= since,
+                        _ => true,
+                    })
+                    .collect();
+                selected[selected.len().saturating_sub(args.max_calls)..]
+                    .iter()
+                    .map(|call| replay_step(call, true))
+                    .collect()
+            }
+        };
+
+        let redactor = Redactor::load()?;
+        let ignore: HashSet<&str> = args.ignore_keys.iter().map(String::as_str).collect();
+        let mut backends: HashMap<String, Result<Backend, String>> = HashMap::new();
+        let mut results = Vec::with_capacity(steps.len());
+
+        for (index, step) in steps.iter().enumerate() {
+            if ctx.is_cancelled() {
+                break;
+            }
+
+            let mut result = ReplayResult {
+                index,
+                tool_name: step.tool_name.clone(),
+                server: step.server.clone(),
+                status: ReplayStatus::Error,
+                reason: None,
+                duration_ms: None,
+                differences: Vec::new(),
+            };
+
+            let Some(&port) = history.ports.get(&step.server) else {
+                result.reason = Some(format!("Server '{}' is not available", step.server));
+                results.push(result);
+                continue;
+            };
+
+            if !backends.contains_key(&step.server) {
+                backends.insert(step.server.clone(), connect(port, args.accept_invalid_hostnames).await);
+            }
+            let backend = match &backends[&step.server] {
+                Ok(backend) => backend,
+                Err(e) => {
+                    result.reason = Some(e.clone());
+                    results.push(result);
+                    continue;
+                }
+            };
+
+            // Only tools the backend declares read-only and non-destructive are executed
+            let annotations = match backend.annotations.get(&step.tool_name) {
+                Some(annotations) => annotations.clone().unwrap_or_default(),
+                None => {
+                    result.status = ReplayStatus::Refused;
+                    result.reason = Some(format!("Tool '{}' is not offered by server '{}'", step.tool_name, step.server));
+                    results.push(result);
+                    continue;
+                }
+            };
+            if annotations.read_only_hint != Some(true) || annotations.destructive_hint == Some(true) {
+                result.status = ReplayStatus::Refused;
+                result.reason = Some(format!("Tool '{}' is not declared read-only and non-destructive", step.tool_name));
+                results.push(result);
+                continue;
+            }
+
+            let arguments = match &step.args {
+                Value::Object(map) => Some(map.clone()),
+                Value::Null => None,
+                _ => {
+                    result.reason = Some("Recorded arguments are not a JSON object".to_string());
+                    results.push(result);
+                    continue;
+                }
+            };
+
+            let started = Instant::now();
+            let replayed = match backend.client
+                .call_tool(CallToolRequestParam {
+                    name: step.tool_name.clone().into(),
+                    arguments,
+                })
+                .await
+            {
+                Ok(output) => serde_json::to_value(&output).unwrap_or_default(),
+                // Same shape the history records for failed calls
+                Err(e) => json!({ "error": e.to_string() }),
+            };
+            result.duration_ms = Some(u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX));
+
+            let mut notes = Vec::new();
+            if annotations.idempotent_hint == Some(false) {
+                notes.push("tool is not idempotent, differences may be expected".to_string());
+            }
+            let failed = is_failure_output(&replayed.to_string());
+            if failed != step.expected_failure {
+                notes.push(if failed { "replay failed but the recorded call succeeded" } else { "replay succeeded but the recorded call failed" }.to_string());
+            }
+
+            result.status = match &step.expected_output {
+                Some(expected) => {
+                    let (path, expected, replayed) = comparable(expected, &replayed);
+                    diff_values(path, expected, replayed, &ignore, &mut result.differences, args.max_differences);
+                    // Differences quote outputs, so mask secrets in them too
+                    for difference in &mut result.differences {
+                        for value in [&mut difference.recorded, &mut difference.replayed].into_iter().flatten() {
+                            redactor.redact_value(value);
+                        }
+                    }
+                    if result.differences.is_empty() { ReplayStatus::Matched } else { ReplayStatus::Drifted }
+                }
+                None => ReplayStatus::Unverified,
+            };
+            result.reason = (!notes.is_empty()).then(|| notes.join("; "));
+            results.push(result);
+        }
+
+        for backend in backends.into_values().flatten() {
+            let _ = backend.client.cancel().await;
+        }
+
+        let count = |status: ReplayStatus| results.iter().filter(|r| r.status == status).count();
+        let matched = count(ReplayStatus::Matched);
+        let drifted = count(ReplayStatus::Drifted);
+        let refused = count(ReplayStatus::Refused);
+        let errors = count(ReplayStatus::Error);
+        let replayed = matched + drifted + count(ReplayStatus::Unverified);
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let summary = style.summary(
+            "Tool Call Replay",
+            &format!(
+                "Replayed: {replayed} · Matched: {matched} · Drifted: {drifted} · Refused: {refused} · Errors: {errors}"
+            ),
+        );
+
+        let output = ReplayToolCallsOutput {
+            success: true,
+            replayed,
+            matched,
+            drifted,
+            refused,
+            errors,
+            results,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
+
+// ============================================================================
+// BACKEND CONNECTION
+// ============================================================================
+
+/// Open an MCP session with a backend server and list its tools
+///
+/// kodegend reports a server's port but not whether it serves TLS, so plain
+/// HTTP is tried first and HTTPS second. A certificate usually names the
+/// server's host rather than the loopback address it is reached on here, so
+/// hostname checks are only skipped when the caller opts in.
+async fn connect(port: u16, accept_invalid_hostnames: bool) -> Result<Backend, String> {
+    let http = connect_uri(format!("http://127.0.0.1:{port}/mcp"), false).await;
+    let Err(http_error) = http else {
+        return http;
+    };
+    connect_uri(format!("https://127.0.0.1:{port}/mcp"), accept_invalid_hostnames)
+        .await
+        .map_err(|https_error| format!("{http_error}; {https_error}"))
+}
+
+async fn connect_uri(uri: String, accept_invalid_hostnames: bool) -> Result<Backend, String> {
+    let http_client = reqwest::Client::builder()
+        .danger_accept_invalid_hostnames(accept_invalid_hostnames)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client for {uri}: {e}"))?;
+    let transport = StreamableHttpClientTransport::with_client(
+        http_client,
+        StreamableHttpClientTransportConfig::with_uri(uri.as_str()),
+    );
+    let client = ()
+        .serve(transport)
+        .await
+        .map_err(|e| format!("Failed to connect to {uri}: {e}"))?;
+
+    let tools = match client.list_all_tools().await {
+        Ok(tools) => tools,
+        Err(e) => {
+            let _ = client.cancel().await;
+            return Err(format!("Failed to list tools at {uri}: {e}"));
+        }
+    };
+
+    Ok(Backend {
+        annotations: tools
+            .into_iter()
+            .map(|tool| (tool.name.into_owned(), tool.annotations))
+            .collect(),
+        client,
+    })
+}
+
+// ============================================================================
+// OUTPUT DIFFING
+// ============================================================================
+
+/// The parts of a recorded and a replayed result worth comparing, with their path
+///
+/// MCP results open with a human-readable summary in `content[0]` that
+/// includes the call duration, so results carrying `structuredContent` on
+/// both sides are compared by that alone.
+fn comparable<'a>(recorded: &'a Value, replayed: &'a Value) -> (&'static str, &'a Value, &'a Value) {
+    match (recorded.get("structuredContent"), replayed.get("structuredContent")) {
+        (Some(recorded), Some(replayed)) => ("/structuredContent", recorded, replayed),
+        _ => ("", recorded, replayed),
+    }
+}
+
+/// Collect differences between two JSON values as JSON Pointer paths
+///
+/// Strings holding JSON on both sides (e.g. structured tool output embedded
+/// in MCP text content) are compared structurally.
+fn diff_values(
+    path: &str,
+    recorded: &Value,
+    replayed: &Value,
+    ignore: &HashSet<&str>,
+    out: &mut Vec<OutputDifference>,
+    limit: usize,
+) {
+    if out.len() >= limit || recorded == replayed {
+        return;
+    }
+
+    match (recorded, replayed) {
+        (Value::Object(a), Value::Object(b)) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys.into_iter().filter(|key| !ignore.contains(key.as_str())) {
+                let child = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                match (a.get(key), b.get(key)) {
+                    (Some(x), Some(y)) => diff_values(&child, x, y, ignore, out, limit),
+                    (x, y) => push(out, limit, child, x.cloned(), y.cloned()),
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let child = format!("{path}/{i}");
+                match (a.get(i), b.get(i)) {
+                    (Some(x), Some(y)) => diff_values(&child, x, y, ignore, out, limit),
+                    (x, y) => push(out, limit, child, x.cloned(), y.cloned()),
+                }
+            }
+        }
+        (Value::String(a), Value::String(b)) => match (embedded_json(a), embedded_json(b)) {
+            (Some(x), Some(y)) => diff_values(path, &x, &y, ignore, out, limit),
+            _ => push(out, limit, path.to_string(), Some(recorded.clone()), Some(replayed.clone())),
+        },
+        _ => push(out, limit, path.to_string(), Some(recorded.clone()), Some(replayed.clone())),
+    }
+}
+
+fn push(out: &mut Vec<OutputDifference>, limit: usize, path: String, recorded: Option<Value>, replayed: Option<Value>) {
+    if out.len() < limit {
+        out.push(OutputDifference { path, recorded, replayed });
+    }
+}
+
+/// Parse a string holding a JSON object or array
+fn embedded_json(text: &str) -> Option<Value> {
+    let trimmed = text.trim_start();
+    if !(trimmed.starts_with('{') || trimmed.starts_with('[')) {
+        return None;
+    }
+    serde_json::from_str(text).ok()
+}
@@ -0,0 +1,118 @@
+//! Schema types for await_tool_call tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::{InspectToolCallsPrompts, ToolCallRecord};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::register_tool_metadata;
+use crate::render::SummaryStyle;
+
+/// Tool name for `await_tool_call`
+pub const AWAIT_TOOL_CALL: &str = "await_tool_call";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for window
+fn default_window() -> usize {
+    10
+}
+
+/// Default value for timeout_secs
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+/// Arguments for `await_tool_call` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct AwaitToolCallArgs {
+    /// Only consider calls of this tool
+    #[serde(default)]
+    pub tool_name: Option<String>,
+
+    /// Only consider calls whose arguments contain this text
+    #[serde(default)]
+    pub args_contains: Option<String>,
+
+    /// Only match failed calls (default: false)
+    #[serde(default)]
+    pub failed_only: bool,
+
+    /// Also match calls recorded at or after this ISO 8601 timestamp (default: only calls completing after the wait starts)
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Instead of a matching call, wait until the failure rate of the last `window` considered calls exceeds this fraction (0.0-1.0)
+    #[serde(default)]
+    pub failure_rate_above: Option<f64>,
+
+    /// Number of most recent calls the failure rate is computed over (default: 10)
+    #[serde(default = "default_window")]
+    pub window: usize,
+
+    /// Instead of a matching call, wait until no considered call has completed for this many seconds
+    #[serde(default)]
+    pub idle_secs: Option<u64>,
+
+    /// Watch another connection instead of the calling one (subject to the access policy)
+    #[serde(default)]
+    pub connection_id: Option<String>,
+
+    /// Watch every known connection (subject to the access policy)
+    #[serde(default)]
+    pub all_connections: bool,
+
+    /// Give up after this many seconds (default: 60, max: 600)
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `await_tool_call` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AwaitToolCallOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Whether the awaited call or condition occurred before the wait ended
+    pub satisfied: bool,
+    /// Why the wait ended: "matched", "failure_rate", "idle", "timeout" or "cancelled"
+    pub stopped_by: String,
+    /// Human-readable description of what was observed
+    pub detail: String,
+    /// How long the wait lasted in milliseconds
+    pub waited_ms: u64,
+    /// The call that ended the wait (matched call, or the newest call in the failure window; redacted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call: Option<ToolCallRecord>,
+    /// Connection that made `call`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_connection: Option<String>,
+    /// Failure rate over the window when the wait ended (failure_rate_above only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_rate: Option<f64>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for AwaitToolCallArgs {
+    type Output = AwaitToolCallOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = AWAIT_TOOL_CALL;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Block until a matching tool call appears in history or a condition such as a failure rate threshold holds";
+}
+
+register_tool_metadata!(AwaitToolCallArgs);
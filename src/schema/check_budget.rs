@@ -0,0 +1,67 @@
+//! Schema types for check_budget tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectUsageStatsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::register_tool_metadata;
+use crate::render::SummaryStyle;
+
+/// Tool name for `check_budget`
+pub const CHECK_BUDGET: &str = "check_budget";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Arguments for `check_budget` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CheckBudgetArgs {
+    /// Tool about to be called
+    pub tool_name: String,
+
+    /// Connection making the call instead of the calling one (subject to the access policy)
+    #[serde(default)]
+    pub connection_id: Option<String>,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `check_budget` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CheckBudgetOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Connection the check applies to
+    pub connection_id: String,
+    /// Tool the check applies to
+    pub tool_name: String,
+    /// Whether one more call fits the session budget
+    pub allowed: bool,
+    /// Limits reached, when the call is not allowed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for CheckBudgetArgs {
+    type Output = CheckBudgetOutput;
+    type Prompts = InspectUsageStatsPrompts;
+
+    const NAME: &'static str = CHECK_BUDGET;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Check whether one more call of a tool fits a connection's per-session budget from introspection-budget.json";
+}
+
+register_tool_metadata!(CheckBudgetArgs);
@@ -0,0 +1,159 @@
+//! Schema types for compare_usage tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectUsageStatsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::register_tool_metadata;
+use crate::render::SummaryStyle;
+
+/// Tool name for `compare_usage`
+pub const COMPARE_USAGE: &str = "compare_usage";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for timeout_minutes
+fn default_timeout_minutes() -> u64 {
+    30
+}
+
+/// One side of a comparison: a session or a time window
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct UsageSlice {
+    /// Session id (see inspect_sessions); mutually exclusive with since/until
+    #[serde(default)]
+    pub session_id: Option<String>,
+
+    /// Start of the window (ISO 8601 format)
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// End of the window (ISO 8601 format)
+    #[serde(default)]
+    pub until: Option<String>,
+}
+
+/// Arguments for `compare_usage` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CompareUsageArgs {
+    /// Reference slice, e.g. the session before a prompt change
+    pub baseline: UsageSlice,
+
+    /// Slice compared against the baseline
+    pub candidate: UsageSlice,
+
+    /// Minutes of inactivity that end a session (default: 30)
+    #[serde(default = "default_timeout_minutes")]
+    pub timeout_minutes: u64,
+
+    /// Compare another connection's history instead of the calling one's (subject to the access policy)
+    #[serde(default)]
+    pub connection_id: Option<String>,
+
+    /// Compare the merged history of every known connection (subject to the access policy)
+    #[serde(default)]
+    pub all_connections: bool,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `compare_usage` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CompareUsageOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Totals of the baseline slice
+    pub baseline: SliceTotals,
+    /// Totals of the candidate slice
+    pub candidate: SliceTotals,
+    /// Candidate minus baseline call count
+    pub calls_delta: i64,
+    /// Candidate minus baseline failure rate (percentage points)
+    pub failure_rate_delta: f64,
+    /// Candidate minus baseline total call duration in milliseconds
+    pub duration_delta_ms: i64,
+    /// Tools only called in the candidate
+    pub new_tools: Vec<String>,
+    /// Tools only called in the baseline
+    pub removed_tools: Vec<String>,
+    /// Per-tool differences, largest call count change first
+    pub tools: Vec<ToolDelta>,
+}
+
+/// Totals of one compared slice
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SliceTotals {
+    /// Session id or time window the slice covers
+    pub label: String,
+    /// Number of calls
+    pub calls: u64,
+    /// Number of failed calls
+    pub failed_calls: u64,
+    /// Failed calls as a percentage of all calls
+    pub failure_rate: f64,
+    /// Number of distinct tools called
+    pub tools_used: usize,
+    /// Sum of recorded call durations in milliseconds
+    pub total_duration_ms: u64,
+    /// First call in the slice (ISO 8601)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_call: Option<String>,
+    /// Last call in the slice (ISO 8601)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_call: Option<String>,
+}
+
+/// How one tool's usage changed between the slices
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolDelta {
+    /// Tool name
+    pub tool_name: String,
+    /// "new", "removed" or "changed"
+    pub change: String,
+    /// Calls in the baseline
+    pub baseline_calls: u64,
+    /// Calls in the candidate
+    pub candidate_calls: u64,
+    /// Candidate minus baseline calls
+    pub calls_delta: i64,
+    /// Failure rate in the baseline (percentage)
+    pub baseline_failure_rate: f64,
+    /// Failure rate in the candidate (percentage)
+    pub candidate_failure_rate: f64,
+    /// Candidate minus baseline failure rate (percentage points)
+    pub failure_rate_delta: f64,
+    /// Average recorded duration in the baseline (ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_avg_ms: Option<f64>,
+    /// Average recorded duration in the candidate (ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidate_avg_ms: Option<f64>,
+    /// Candidate minus baseline average duration (ms, when both have durations)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_ms_delta: Option<f64>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for CompareUsageArgs {
+    type Output = CompareUsageOutput;
+    type Prompts = InspectUsageStatsPrompts;
+
+    const NAME: &'static str = COMPARE_USAGE;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Compare tool usage between two sessions or time windows: call counts, latencies, failure rates and new or removed tools";
+}
+
+register_tool_metadata!(CompareUsageArgs);
@@ -0,0 +1,133 @@
+//! Schema types for export_session tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::register_tool_metadata;
+use crate::render::SummaryStyle;
+
+/// Tool name for `export_session`
+pub const EXPORT_SESSION: &str = "export_session";
+
+/// Format version of [`ReplayScript`]
+pub const REPLAY_SCRIPT_VERSION: u32 = 1;
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for timeout_minutes
+fn default_timeout_minutes() -> u64 {
+    30
+}
+
+/// Default value for include_outputs
+fn default_include_outputs() -> bool {
+    true
+}
+
+/// Arguments for `export_session` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ExportSessionArgs {
+    /// Session id to export (see inspect_sessions; exports all history when omitted)
+    #[serde(default)]
+    pub session_id: Option<String>,
+
+    /// Minutes of inactivity that end a session (default: 30)
+    #[serde(default = "default_timeout_minutes")]
+    pub timeout_minutes: u64,
+
+    /// Only export calls since this timestamp (ISO 8601 format)
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Only export calls up to this timestamp (ISO 8601 format)
+    #[serde(default)]
+    pub until: Option<String>,
+
+    /// Include captured outputs as expectations (default: true)
+    #[serde(default = "default_include_outputs")]
+    pub include_outputs: bool,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+
+    /// Replace home directories, usernames, hostnames and e-mail addresses with stable pseudonyms for sharing (default: false)
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `export_session` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExportSessionOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Number of exported steps
+    pub count: usize,
+    /// Number of secrets masked in step arguments and expected outputs
+    pub redactions: usize,
+    /// The replay script
+    pub script: ReplayScript,
+}
+
+/// Ordered tool calls that reproduce an agent run through any MCP client
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReplayScript {
+    /// Script format version
+    pub version: u32,
+    /// When the script was exported (ISO 8601)
+    pub exported_at: String,
+    /// Exported session id (None when exporting a time range or all history)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Steps in call order
+    pub steps: Vec<ReplayStep>,
+}
+
+/// A single call to replay
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReplayStep {
+    /// Tool to call
+    pub tool_name: String,
+    /// Arguments to pass
+    pub args: Value,
+    /// Backend server that executed the original call
+    #[serde(default)]
+    pub server: String,
+    /// Timestamp of the original call (ISO 8601)
+    #[serde(default)]
+    pub timestamp: String,
+    /// Duration of the original call in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Output captured from the original call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_output: Option<Value>,
+    /// Whether the original call failed
+    #[serde(default)]
+    pub expected_failure: bool,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for ExportSessionArgs {
+    type Output = ExportSessionOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = EXPORT_SESSION;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Export a session or time range of tool history as a replay script: ordered tool names and arguments with captured outputs as expectations";
+}
+
+register_tool_metadata!(ExportSessionArgs);
@@ -0,0 +1,98 @@
+//! Schema types for inspect_alerts tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::register_tool_metadata;
+use crate::alerts::FiredAlert;
+use crate::render::SummaryStyle;
+
+/// Tool name for `inspect_alerts`
+pub const INSPECT_ALERTS: &str = "inspect_alerts";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for limit
+fn default_limit() -> usize {
+    50
+}
+
+/// Arguments for `inspect_alerts` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct InspectAlertsArgs {
+    /// Alerts of another connection instead of the calling one (subject to the access policy)
+    #[serde(default)]
+    pub connection_id: Option<String>,
+
+    /// Alerts of every known connection (subject to the access policy)
+    #[serde(default)]
+    pub all_connections: bool,
+
+    /// Only alerts raised by this rule
+    #[serde(default)]
+    pub rule: Option<String>,
+
+    /// Only alerts whose condition still holds (default: false)
+    #[serde(default)]
+    pub active_only: bool,
+
+    /// Only alerts fired at or after this ISO 8601 timestamp
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Maximum number of alerts to return, most recent first (default: 50)
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `inspect_alerts` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectAlertsOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Number of alerts returned
+    pub count: usize,
+    /// Number of returned alerts that are still active
+    pub active: usize,
+    /// Names of the rules configured for this working directory
+    pub rules: Vec<String>,
+    /// Alerts, most recent first
+    pub alerts: Vec<AlertEntry>,
+}
+
+/// A fired alert and the connection it was raised for
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AlertEntry {
+    /// Connection whose calls triggered the alert
+    pub connection_id: String,
+    #[serde(flatten)]
+    pub alert: FiredAlert,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for InspectAlertsArgs {
+    type Output = InspectAlertsOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = INSPECT_ALERTS;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "List alerts raised by the configured alert rules (failure rates, latency percentiles, call counts)";
+}
+
+register_tool_metadata!(InspectAlertsArgs);
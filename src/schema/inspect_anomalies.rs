@@ -0,0 +1,114 @@
+//! Schema types for inspect_anomalies tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::register_tool_metadata;
+use crate::anomaly::Anomaly;
+use crate::render::SummaryStyle;
+
+/// Tool name for `inspect_anomalies`
+pub const INSPECT_ANOMALIES: &str = "inspect_anomalies";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for bucket_secs
+fn default_bucket_secs() -> u64 {
+    60
+}
+
+/// Default value for baseline_buckets
+fn default_baseline_buckets() -> usize {
+    20
+}
+
+/// Default value for alpha
+fn default_alpha() -> f64 {
+    0.3
+}
+
+/// Default value for threshold
+fn default_threshold() -> f64 {
+    3.5
+}
+
+/// Arguments for `inspect_anomalies` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct InspectAnomaliesArgs {
+    /// Only analyze calls of this tool
+    #[serde(default)]
+    pub tool_name: Option<String>,
+
+    /// Only analyze calls since this timestamp (ISO 8601 format)
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Analyze another connection instead of the calling one (subject to the access policy)
+    #[serde(default)]
+    pub connection_id: Option<String>,
+
+    /// Analyze every known connection together (subject to the access policy)
+    #[serde(default)]
+    pub all_connections: bool,
+
+    /// Width of each time bucket in seconds (default: 60); the history may span at most 10,000 buckets
+    #[serde(default = "default_bucket_secs")]
+    pub bucket_secs: u64,
+
+    /// Number of preceding buckets the rolling baseline is built from (default: 20)
+    #[serde(default = "default_baseline_buckets")]
+    pub baseline_buckets: usize,
+
+    /// EWMA smoothing factor between 0 and 1; higher follows recent buckets more closely (default: 0.3)
+    #[serde(default = "default_alpha")]
+    pub alpha: f64,
+
+    /// Robust z-score (deviations from the baseline in scaled MADs) above which a bucket is flagged (default: 3.5)
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `inspect_anomalies` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectAnomaliesOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Number of calls analyzed
+    pub calls_scanned: usize,
+    /// Number of time buckets analyzed
+    pub buckets: usize,
+    /// Width of each bucket in seconds
+    pub bucket_secs: u64,
+    /// Number of anomalous intervals
+    pub count: usize,
+    /// Anomalous intervals, highest score first
+    pub anomalies: Vec<Anomaly>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for InspectAnomaliesArgs {
+    type Output = InspectAnomaliesOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = INSPECT_ANOMALIES;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Flag call-rate spikes, latency regressions and failure bursts against a rolling EWMA/MAD baseline";
+}
+
+register_tool_metadata!(InspectAnomaliesArgs);
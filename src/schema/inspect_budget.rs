@@ -0,0 +1,83 @@
+//! Schema types for inspect_budget tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectUsageStatsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::register_tool_metadata;
+use crate::budget::BudgetStatus;
+use crate::render::SummaryStyle;
+
+/// Tool name for `inspect_budget`
+pub const INSPECT_BUDGET: &str = "inspect_budget";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Arguments for `inspect_budget` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct InspectBudgetArgs {
+    /// Budget of another connection instead of the calling one (subject to the access policy)
+    #[serde(default)]
+    pub connection_id: Option<String>,
+
+    /// Budgets of every known connection (subject to the access policy)
+    #[serde(default)]
+    pub all_connections: bool,
+
+    /// Only limits that constrain calls of this tool
+    #[serde(default)]
+    pub tool_name: Option<String>,
+
+    /// Only sessions that reached a limit (default: false)
+    #[serde(default)]
+    pub exhausted_only: bool,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `inspect_budget` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectBudgetOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Whether any budget is configured
+    pub configured: bool,
+    /// Number of sessions that reached a limit
+    pub exhausted: usize,
+    /// Budget use of each connection's current session
+    pub budgets: Vec<SessionBudget>,
+}
+
+/// Budget use of one connection's current session
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SessionBudget {
+    /// Connection the usage belongs to
+    pub connection_id: String,
+    #[serde(flatten)]
+    pub status: BudgetStatus,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for InspectBudgetArgs {
+    type Output = InspectBudgetOutput;
+    type Prompts = InspectUsageStatsPrompts;
+
+    const NAME: &'static str = INSPECT_BUDGET;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Show remaining per-session budgets (calls per tool, total duration, output bytes) from introspection-budget.json";
+}
+
+register_tool_metadata!(InspectBudgetArgs);
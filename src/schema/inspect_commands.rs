@@ -0,0 +1,119 @@
+//! Schema types for inspect_commands tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::render::SummaryStyle;
+
+use super::register_tool_metadata;
+
+/// Tool name for `inspect_commands`
+pub const INSPECT_COMMANDS: &str = "inspect_commands";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for max_results
+fn default_max_results() -> usize {
+    100
+}
+
+/// Default value for max_output_chars
+fn default_max_output_chars() -> usize {
+    500
+}
+
+/// Arguments for `inspect_commands` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct InspectCommandsArgs {
+    /// Maximum number of commands to return, most recent kept (default: 100)
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+
+    /// Only return commands since this timestamp (ISO 8601 format)
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Only return commands that failed or exited non-zero (default: false)
+    #[serde(default)]
+    pub failed_only: bool,
+
+    /// Characters of output kept per command (default: 500, 0 to omit output)
+    #[serde(default = "default_max_output_chars")]
+    pub max_output_chars: usize,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+
+    /// Replace home directories, usernames, hostnames and e-mail addresses with stable pseudonyms for sharing (default: false)
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `inspect_commands` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectCommandsOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Number of commands returned
+    pub count: usize,
+    /// Number of commands matching the filters before max_results was applied
+    pub total_commands: usize,
+    /// Commands, oldest first
+    pub commands: Vec<CommandRecord>,
+    /// Shell-history-like view, one line per command
+    pub history: String,
+    /// Number of secrets masked in commands and outputs
+    pub redactions: usize,
+}
+
+/// A command executed through a terminal or process tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CommandRecord {
+    /// Timestamp of the call (ISO 8601)
+    pub timestamp: String,
+    /// Tool that executed the command
+    pub tool_name: String,
+    /// Command line (or tool arguments for tools without a command)
+    pub command: String,
+    /// Exit code, if reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i64>,
+    /// Working directory, if reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Duration in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Whether the call failed or exited non-zero
+    pub failed: bool,
+    /// Captured output, truncated to max_output_chars
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Whether the output was truncated
+    pub output_truncated: bool,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for InspectCommandsArgs {
+    type Output = InspectCommandsOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = INSPECT_COMMANDS;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Audit commands executed through terminal and process tools as a shell-history-like log with exit codes, durations and truncated output";
+}
+
+register_tool_metadata!(InspectCommandsArgs);
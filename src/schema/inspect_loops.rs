@@ -0,0 +1,117 @@
+//! Schema types for inspect_loops tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::render::SummaryStyle;
+
+use super::register_tool_metadata;
+
+/// Tool name for `inspect_loops`
+pub const INSPECT_LOOPS: &str = "inspect_loops";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for min_repeats
+fn default_min_repeats() -> usize {
+    3
+}
+
+/// Arguments for `inspect_loops` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct InspectLoopsArgs {
+    /// Minimum number of repeated calls reported as a loop (default: 3)
+    #[serde(default = "default_min_repeats")]
+    pub min_repeats: usize,
+
+    /// Only scan calls since this timestamp (ISO 8601 format)
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `inspect_loops` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectLoopsOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Number of calls scanned
+    pub calls_scanned: usize,
+    /// Number of findings
+    pub count: usize,
+    /// Findings, most severe first
+    pub findings: Vec<LoopFinding>,
+    /// Number of secrets masked in finding descriptions
+    pub redactions: usize,
+}
+
+/// Kind of wasteful pattern detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LoopKind {
+    /// Same tool called with identical arguments
+    IdenticalCalls,
+    /// Same tool called with arguments differing only in numbers or case/whitespace
+    NearIdenticalCalls,
+    /// Edits or writes to a file that undo a previous change
+    Oscillation,
+    /// Repeated calls to a tool right after it failed
+    RetryStorm,
+}
+
+/// A detected loop or thrash pattern
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LoopFinding {
+    /// Kind of pattern
+    pub kind: LoopKind,
+    /// Tool involved
+    pub tool_name: String,
+    /// Severity from 0.0 (benign) to 1.0 (severe)
+    pub severity: f64,
+    /// Involved calls, oldest first
+    pub calls: Vec<LoopCall>,
+    /// Timestamp of the first involved call (ISO 8601)
+    pub first_seen: String,
+    /// Timestamp of the last involved call (ISO 8601)
+    pub last_seen: String,
+    /// Human-readable explanation
+    pub description: String,
+}
+
+/// A call involved in a finding, identified by when it completed and what it called
+///
+/// History files rotate, so positions in the history are not stable ids.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct LoopCall {
+    /// Completion timestamp of the call (ISO 8601)
+    pub timestamp: String,
+    /// Tool that was called
+    pub tool_name: String,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for InspectLoopsArgs {
+    type Output = InspectLoopsOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = INSPECT_LOOPS;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Detect repeated identical calls, edit/undo oscillation and retry storms in tool history, with the involved calls (timestamp and tool) and a severity score";
+}
+
+register_tool_metadata!(InspectLoopsArgs);
@@ -0,0 +1,151 @@
+//! Schema types for inspect_session_summary tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::render::SummaryStyle;
+
+use super::register_tool_metadata;
+
+/// Tool name for `inspect_session_summary`
+pub const INSPECT_SESSION_SUMMARY: &str = "inspect_session_summary";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for timeout_minutes
+fn default_timeout_minutes() -> u64 {
+    30
+}
+
+/// Default value for max_tokens
+fn default_max_tokens() -> usize {
+    2000
+}
+
+/// Arguments for `inspect_session_summary` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct InspectSessionSummaryArgs {
+    /// Summarize only this session (optional, summarizes all history when omitted)
+    #[serde(default)]
+    pub session_id: Option<String>,
+
+    /// Minutes of inactivity that end a session (default: 30)
+    #[serde(default = "default_timeout_minutes")]
+    pub timeout_minutes: u64,
+
+    /// Approximate token budget for the summary (default: 2000)
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: usize,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+
+    /// Replace home directories, usernames, hostnames and e-mail addresses with stable pseudonyms for sharing (default: false)
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `inspect_session_summary` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectSessionSummaryOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Session summarized (None when summarizing all history)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Number of calls summarized
+    pub total_calls: usize,
+    /// Number of failed calls
+    pub failed_calls: usize,
+    /// Files read, first occurrence order
+    pub files_read: Vec<String>,
+    /// Files created, overwritten, moved or deleted
+    pub files_written: Vec<String>,
+    /// Files edited in place
+    pub files_edited: Vec<String>,
+    /// Commands run
+    pub commands: Vec<String>,
+    /// Search patterns used
+    pub searches: Vec<String>,
+    /// Errors hit
+    pub errors: Vec<SummaryError>,
+    /// Work phases, oldest first
+    pub phases: Vec<SummaryPhase>,
+    /// Narrative summary sized to the token budget
+    pub narrative: String,
+    /// Estimated tokens of the narrative
+    pub estimated_tokens: usize,
+    /// Token budget requested
+    pub max_tokens: usize,
+    /// Whether lists were shortened to fit the budget
+    pub truncated: bool,
+    /// Number of secrets masked in commands, searches, errors and the narrative
+    pub redactions: usize,
+}
+
+/// Kind of work done in a phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseKind {
+    /// Reading files and searching
+    Explore,
+    /// Writing and editing files
+    Modify,
+    /// Running commands
+    Execute,
+    /// Calls that fit none of the above
+    Other,
+}
+
+/// A contiguous run of calls doing the same kind of work
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SummaryPhase {
+    /// Kind of work done
+    pub kind: PhaseKind,
+    /// Timestamp of the first call (ISO 8601)
+    pub start: String,
+    /// Timestamp of the last call (ISO 8601)
+    pub end: String,
+    /// Number of calls in the phase
+    pub call_count: usize,
+    /// Number of failed calls in the phase
+    pub failed_calls: usize,
+    /// Notable actions ("read src/lib.rs", "ran `cargo test`")
+    pub highlights: Vec<String>,
+}
+
+/// A failed call
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SummaryError {
+    /// Tool that failed
+    pub tool_name: String,
+    /// Timestamp of the call (ISO 8601)
+    pub timestamp: String,
+    /// Error message (truncated)
+    pub message: String,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for InspectSessionSummaryArgs {
+    type Output = InspectSessionSummaryOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = INSPECT_SESSION_SUMMARY;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Condense tool history into a narrative of files read and written, commands run, searches and errors, grouped by work phase and sized to a token budget";
+}
+
+register_tool_metadata!(InspectSessionSummaryArgs);
@@ -0,0 +1,76 @@
+//! Schema types for inspect_sessions tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::{InspectToolCallsPrompts, ToolCallRecord};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::register_tool_metadata;
+use crate::render::SummaryStyle;
+use crate::sessions::SessionRecord;
+
+/// Tool name for `inspect_sessions`
+pub const INSPECT_SESSIONS: &str = "inspect_sessions";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for timeout_minutes
+fn default_timeout_minutes() -> u64 {
+    30
+}
+
+/// Arguments for `inspect_sessions` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct InspectSessionsArgs {
+    /// Session id to drill into (optional, lists all sessions when omitted)
+    #[serde(default)]
+    pub session_id: Option<String>,
+
+    /// Minutes of inactivity that end a session (default: 30)
+    #[serde(default = "default_timeout_minutes")]
+    pub timeout_minutes: u64,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `inspect_sessions` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectSessionsOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Number of sessions found
+    pub count: usize,
+    /// Session timeout used for segmentation (minutes)
+    pub timeout_minutes: u64,
+    /// All sessions, oldest first
+    pub sessions: Vec<SessionRecord>,
+    /// Calls of the requested session (only when session_id was given)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_calls: Option<Vec<ToolCallRecord>>,
+    /// Number of secrets masked in the returned session calls
+    pub redactions: usize,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for InspectSessionsArgs {
+    type Output = InspectSessionsOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = INSPECT_SESSIONS;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "List tool call sessions with their boundaries, call counts, failures and tools used, or drill into a single session's calls";
+}
+
+register_tool_metadata!(InspectSessionsArgs);
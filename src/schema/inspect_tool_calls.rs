@@ -0,0 +1,114 @@
+//! Extended schema types for inspect_tool_calls tool
+
+use kodegen_config::{CATEGORY_INTROSPECTION, INSPECT_TOOL_CALLS};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::{self, InspectToolCallsPrompts, ToolCallRecord};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::render::SummaryStyle;
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Export format for `inspect_tool_calls` results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallsFormat {
+    /// Structured output only (default)
+    #[default]
+    Json,
+    /// One JSON object per call
+    Jsonl,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Markdown table (args/output cells truncated)
+    Markdown,
+    /// Chrome Trace Event JSON for chrome://tracing or Perfetto, one track per server
+    ChromeTrace,
+}
+
+/// Arguments for `inspect_tool_calls` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct InspectToolCallsArgs {
+    #[serde(flatten)]
+    pub base: introspection::InspectToolCallsArgs,
+
+    /// Export format of the display output: "json" (default), "jsonl", "csv", "markdown" or "chrome_trace"
+    #[serde(default)]
+    pub format: ToolCallsFormat,
+
+    /// Inspect another connection's history instead of the calling one (subject to the access policy)
+    #[serde(default)]
+    pub connection_id: Option<String>,
+
+    /// Merge the history of every known connection, tagging each call with its connection (subject to the access policy)
+    #[serde(default)]
+    pub all_connections: bool,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+
+    /// Replace home directories, usernames, hostnames and e-mail addresses with stable pseudonyms for sharing (default: false)
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// A tool call record tagged with the connection that made it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConnectionCall {
+    /// Connection that made the call (cross-connection views only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_id: Option<String>,
+    #[serde(flatten)]
+    pub record: ToolCallRecord,
+}
+
+/// Output from `inspect_tool_calls` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectToolCallsOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Number of calls returned
+    pub count: usize,
+    /// Total entries in memory
+    pub total_entries_in_memory: usize,
+    /// Tool call records, tagged with their connection in cross-connection views
+    pub calls: Vec<ConnectionCall>,
+    /// Filter applied (tool name)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_tool_name: Option<String>,
+    /// Filter applied (since timestamp)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_since: Option<String>,
+    /// Offset used for pagination
+    pub offset: i64,
+    /// Max results requested
+    pub max_results: usize,
+    /// Format of the display output
+    pub format: ToolCallsFormat,
+    /// Number of secrets masked in the returned arguments and outputs
+    pub redactions: usize,
+    /// Connections whose history was queried (cross-connection views only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connections: Vec<String>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for InspectToolCallsArgs {
+    type Output = InspectToolCallsOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = INSPECT_TOOL_CALLS;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = <introspection::InspectToolCallsArgs as ToolArgs>::DESCRIPTION;
+}
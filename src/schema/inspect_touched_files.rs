@@ -0,0 +1,100 @@
+//! Schema types for inspect_touched_files tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::render::SummaryStyle;
+
+use super::register_tool_metadata;
+
+/// Tool name for `inspect_touched_files`
+pub const INSPECT_TOUCHED_FILES: &str = "inspect_touched_files";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for max_results
+fn default_max_results() -> usize {
+    100
+}
+
+/// Arguments for `inspect_touched_files` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct InspectTouchedFilesArgs {
+    /// Only report paths starting with this prefix (optional)
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+
+    /// Only report files that were written or edited (default: false)
+    #[serde(default)]
+    pub modified_only: bool,
+
+    /// Only count calls since this timestamp (ISO 8601 format)
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Maximum number of files to return, most recently touched first (default: 100)
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+
+    /// Replace home directories, usernames, hostnames and e-mail addresses with stable pseudonyms for sharing (default: false)
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `inspect_touched_files` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectTouchedFilesOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Number of files returned
+    pub count: usize,
+    /// Number of files matching the filters before max_results was applied
+    pub total_files: usize,
+    /// Touched files, most recently touched first
+    pub files: Vec<TouchedFile>,
+}
+
+/// Access counts for a single path
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TouchedFile {
+    /// Path as passed to the tool
+    pub path: String,
+    /// Number of reads
+    pub reads: usize,
+    /// Number of writes (create, overwrite, move, delete)
+    pub writes: usize,
+    /// Number of in-place edits
+    pub edits: usize,
+    /// Timestamp of the last call touching the path (ISO 8601)
+    pub last_touched: String,
+    /// Tool used in the last call touching the path
+    pub last_tool: String,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for InspectTouchedFilesArgs {
+    type Output = InspectTouchedFilesOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = INSPECT_TOUCHED_FILES;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "List every file path read, written or edited through filesystem tools, with per-path access counts and last-touched time";
+}
+
+register_tool_metadata!(InspectTouchedFilesArgs);
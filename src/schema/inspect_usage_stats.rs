@@ -0,0 +1,176 @@
+//! Extended schema types for inspect_usage_stats tool
+
+use kodegen_config::{CATEGORY_INTROSPECTION, INSPECT_USAGE_STATS};
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::{self, InspectUsageStatsPrompts};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::context_cost::TokenHeuristic;
+use crate::render::SummaryStyle;
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Which statistics `inspect_usage_stats` reports on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageScope {
+    /// Live statistics for the calling connection, queried from kodegend
+    #[default]
+    Connection,
+    /// A single instance's stats file on disk (requires `instance_id`)
+    Instance,
+    /// Lifetime totals merged from every instance's stats file on disk
+    AllInstances,
+}
+
+/// Export format for `inspect_usage_stats` results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageFormat {
+    /// Structured output only (default)
+    #[default]
+    Json,
+    /// One JSON object per tool
+    Jsonl,
+    /// Comma-separated values with a header row, one row per tool
+    Csv,
+    /// Totals line followed by a per-tool Markdown table
+    Markdown,
+}
+
+/// Arguments for `inspect_usage_stats` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct InspectUsageStatsArgs {
+    #[serde(flatten)]
+    pub base: introspection::InspectUsageStatsArgs,
+
+    /// Statistics scope: "connection" (default), "instance" or "all_instances"
+    #[serde(default)]
+    pub scope: UsageScope,
+
+    /// Instance id to report on when scope is "instance"
+    #[serde(default)]
+    pub instance_id: Option<String>,
+
+    /// Report another connection instead of the calling one when scope is "connection" (subject to the access policy)
+    #[serde(default)]
+    pub connection_id: Option<String>,
+
+    /// Merge every known connection when scope is "connection", with a per-connection breakdown (subject to the access policy)
+    #[serde(default)]
+    pub all_connections: bool,
+
+    /// Token heuristic for context costs computed from history: "chars" (default) or "words"
+    #[serde(default)]
+    pub tokenizer: TokenHeuristic,
+
+    /// Export format of the display output: "json" (default), "jsonl", "csv" or "markdown"
+    #[serde(default)]
+    pub format: UsageFormat,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+
+    /// Replace home directories, usernames, hostnames and e-mail addresses with stable pseudonyms for sharing (default: false)
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `inspect_usage_stats` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectUsageOutput {
+    #[serde(flatten)]
+    pub base: introspection::InspectUsageOutput,
+    /// Scope the statistics were gathered for
+    pub scope: UsageScope,
+    /// Format of the display output
+    #[serde(default)]
+    pub format: UsageFormat,
+    /// Per-instance breakdown (instance and all_instances scopes only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub instances: Vec<InstanceUsage>,
+    /// Per-connection breakdown (connection scope with connection_id or all_connections only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connections: Vec<ConnectionUsage>,
+    /// Tools ranked by estimated context tokens consumed, most expensive first
+    #[serde(default)]
+    pub context_costs: Vec<ToolContextCost>,
+}
+
+/// Context consumed by one tool's arguments and outputs
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolContextCost {
+    /// Tool name
+    pub tool_name: String,
+    /// Number of calls measured
+    pub calls: u64,
+    /// Total size of serialized arguments in bytes
+    pub args_bytes: u64,
+    /// Total size of serialized outputs in bytes
+    pub output_bytes: u64,
+    /// Estimated tokens for arguments and outputs combined
+    pub estimated_tokens: u64,
+    /// Average estimated tokens per call
+    pub avg_tokens_per_call: u64,
+    /// Share of all estimated tokens (percentage)
+    pub share: f64,
+}
+
+/// Live usage totals for a single connection
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConnectionUsage {
+    /// Connection id
+    pub connection_id: String,
+    /// Total number of tool calls
+    pub total_calls: u64,
+    /// Number of successful calls
+    pub successful_calls: u64,
+    /// Number of failed calls
+    pub failed_calls: u64,
+    /// Number of unique tools used
+    pub tools_used: usize,
+}
+
+/// Usage totals for a single instance stats file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InstanceUsage {
+    /// Instance id (from `stats_{instance_id}.json`)
+    pub instance_id: String,
+    /// Total number of tool calls
+    pub total_calls: u64,
+    /// Number of successful calls
+    pub successful_calls: u64,
+    /// Number of failed calls
+    pub failed_calls: u64,
+    /// Number of unique tools used
+    pub tools_used: usize,
+    /// Number of connections recorded in the file
+    pub connections: usize,
+    /// Number of sessions recorded
+    pub total_sessions: u64,
+    /// First activity (Unix timestamp)
+    pub first_used: i64,
+    /// Last activity (Unix timestamp)
+    pub last_used: i64,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for InspectUsageStatsArgs {
+    type Output = InspectUsageOutput;
+    type Prompts = InspectUsageStatsPrompts;
+
+    const NAME: &'static str = INSPECT_USAGE_STATS;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = <introspection::InspectUsageStatsArgs as ToolArgs>::DESCRIPTION;
+}
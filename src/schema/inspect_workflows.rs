@@ -0,0 +1,133 @@
+//! Schema types for inspect_workflows tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::render::SummaryStyle;
+
+use super::register_tool_metadata;
+
+/// Tool name for `inspect_workflows`
+pub const INSPECT_WORKFLOWS: &str = "inspect_workflows";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for sequence_length
+fn default_sequence_length() -> usize {
+    3
+}
+
+/// Default value for max_results
+fn default_max_results() -> usize {
+    10
+}
+
+/// Default value for timeout_minutes
+fn default_timeout_minutes() -> u64 {
+    30
+}
+
+/// Arguments for `inspect_workflows` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct InspectWorkflowsArgs {
+    /// Length of mined call sequences (n-grams), 2 to 6 (default: 3)
+    #[serde(default = "default_sequence_length")]
+    pub sequence_length: usize,
+
+    /// Maximum number of sequences and rare transitions returned (default: 10)
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+
+    /// Only use calls since this timestamp (ISO 8601 format)
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Inactivity gap that breaks a workflow, in minutes (default: 30)
+    #[serde(default = "default_timeout_minutes")]
+    pub timeout_minutes: u64,
+
+    /// Include the transition graph rendered as Graphviz DOT
+    #[serde(default)]
+    pub dot: bool,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `inspect_workflows` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InspectWorkflowsOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Number of calls scanned
+    pub calls_scanned: usize,
+    /// Number of sessions the calls were split into
+    pub sessions: usize,
+    /// Call count per tool, most used first
+    pub tools: Vec<WorkflowNode>,
+    /// Tool-to-tool transitions, most frequent first
+    pub transitions: Vec<WorkflowTransition>,
+    /// Most frequent call sequences
+    pub sequences: Vec<WorkflowSequence>,
+    /// Transitions that are unusual for their source tool, least likely first
+    pub rare_transitions: Vec<WorkflowTransition>,
+    /// Transition graph in Graphviz DOT format (when requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dot: Option<String>,
+}
+
+/// A tool in the transition graph
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowNode {
+    /// Tool name
+    pub tool_name: String,
+    /// Number of calls
+    pub calls: u64,
+}
+
+/// A directed edge between two consecutive tool calls
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowTransition {
+    /// Tool called first
+    pub from: String,
+    /// Tool called next
+    pub to: String,
+    /// Number of times this transition occurred
+    pub count: u64,
+    /// Share of transitions leaving `from` that go to `to` (0.0 to 1.0)
+    pub probability: f64,
+}
+
+/// A recurring sequence of consecutive tool calls
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowSequence {
+    /// Tool names in call order
+    pub tools: Vec<String>,
+    /// Number of occurrences
+    pub count: u64,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for InspectWorkflowsArgs {
+    type Output = InspectWorkflowsOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = INSPECT_WORKFLOWS;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Tool-to-tool transition graph and most frequent call sequences mined from tool history, with rare transitions and optional Graphviz DOT export";
+}
+
+register_tool_metadata!(InspectWorkflowsArgs);
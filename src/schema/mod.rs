@@ -0,0 +1,80 @@
+//! Local schema bindings for introspection tools
+//!
+//! Args and Output types here extend the published types in
+//! `kodegen_mcp_schema::introspection` with options that have not been
+//! upstreamed yet, or define tools that only exist in this crate. Extended
+//! types flatten the published type, so existing clients keep deserializing
+//! the same JSON.
+//!
+//! Prompt providers are sealed to the schema package, so every binding
+//! reuses the closest published introspection prompt provider.
+
+pub mod await_tool_call;
+pub mod check_budget;
+pub mod compare_usage;
+pub mod export_session;
+pub mod inspect_alerts;
+pub mod inspect_anomalies;
+pub mod inspect_budget;
+pub mod inspect_commands;
+pub mod inspect_loops;
+pub mod inspect_session_summary;
+pub mod inspect_sessions;
+pub mod inspect_tool_calls;
+pub mod inspect_touched_files;
+pub mod inspect_usage_stats;
+pub mod inspect_workflows;
+pub mod replay_tool_calls;
+pub mod watch_tool_calls;
+
+pub use await_tool_call::AWAIT_TOOL_CALL;
+pub use check_budget::CHECK_BUDGET;
+pub use compare_usage::COMPARE_USAGE;
+pub use export_session::EXPORT_SESSION;
+pub use inspect_alerts::INSPECT_ALERTS;
+pub use inspect_anomalies::INSPECT_ANOMALIES;
+pub use inspect_budget::INSPECT_BUDGET;
+pub use inspect_commands::INSPECT_COMMANDS;
+pub use inspect_loops::INSPECT_LOOPS;
+pub use inspect_session_summary::INSPECT_SESSION_SUMMARY;
+pub use inspect_sessions::INSPECT_SESSIONS;
+pub use inspect_touched_files::INSPECT_TOUCHED_FILES;
+pub use inspect_workflows::INSPECT_WORKFLOWS;
+pub use replay_tool_calls::REPLAY_TOOL_CALLS;
+pub use watch_tool_calls::WATCH_TOOL_CALLS;
+
+/// Register `ToolMetadata` for a tool defined in this crate
+///
+/// Mirrors the upstream `#[tool_metadata]` attribute so local tools are
+/// discoverable and categorized like published ones.
+macro_rules! register_tool_metadata {
+    ($args:ty) => {
+        inventory::submit! {
+            kodegen_mcp_schema::ToolMetadata {
+                name: <$args as kodegen_mcp_schema::ToolArgs>::NAME,
+                category: <$args as kodegen_mcp_schema::ToolArgs>::CATEGORY,
+                description: <$args as kodegen_mcp_schema::ToolArgs>::DESCRIPTION,
+                args_schema: || {
+                    let schema = schemars::schema_for!($args);
+                    serde_json::to_value(&schema).expect("Failed to serialize schema")
+                },
+                output_schema: || {
+                    let schema = schemars::schema_for!(<$args as kodegen_mcp_schema::ToolArgs>::Output);
+                    serde_json::to_value(&schema).expect("Failed to serialize schema")
+                },
+                prompt_arguments: || {
+                    <<$args as kodegen_mcp_schema::ToolArgs>::Prompts as kodegen_mcp_schema::tool::PromptProvider>::prompt_arguments()
+                },
+                generate_prompts: |args_json: &serde_json::Value| {
+                    let args: <<$args as kodegen_mcp_schema::ToolArgs>::Prompts as kodegen_mcp_schema::tool::PromptProvider>::PromptArgs =
+                        serde_json::from_value(args_json.clone())
+                            .or_else(|_| serde_json::from_value(serde_json::json!({})))
+                            .expect("PromptArgs should deserialize from empty object");
+                    <<$args as kodegen_mcp_schema::ToolArgs>::Prompts as kodegen_mcp_schema::tool::PromptProvider>::generate_prompts(&args)
+                },
+            }
+        }
+    };
+}
+
+pub(crate) use register_tool_metadata;
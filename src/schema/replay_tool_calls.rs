@@ -0,0 +1,153 @@
+//! Schema types for replay_tool_calls tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::export_session::ReplayScript;
+use super::register_tool_metadata;
+use crate::render::SummaryStyle;
+
+/// Tool name for `replay_tool_calls`
+pub const REPLAY_TOOL_CALLS: &str = "replay_tool_calls";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for max_calls
+fn default_max_calls() -> usize {
+    20
+}
+
+/// Default value for max_differences
+fn default_max_differences() -> usize {
+    20
+}
+
+/// Arguments for `replay_tool_calls` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ReplayToolCallsArgs {
+    /// Replay script from export_session (replays recent history when omitted)
+    #[serde(default)]
+    pub script: Option<ReplayScript>,
+
+    /// Only replay history calls of this tool (ignored with script)
+    #[serde(default)]
+    pub tool_name: Option<String>,
+
+    /// Only replay history calls since this timestamp, ISO 8601 (ignored with script)
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Maximum number of most recent history calls to replay (default: 20, ignored with script)
+    #[serde(default = "default_max_calls")]
+    pub max_calls: usize,
+
+    /// Object keys ignored when diffing outputs, at any depth (e.g. "duration_ms")
+    #[serde(default)]
+    pub ignore_keys: Vec<String>,
+
+    /// Maximum differences reported per call (default: 20)
+    #[serde(default = "default_max_differences")]
+    pub max_differences: usize,
+
+    /// Accept HTTPS backends whose certificate does not name 127.0.0.1 (default: false).
+    /// The certificate chain is still verified.
+    #[serde(default)]
+    pub accept_invalid_hostnames: bool,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `replay_tool_calls` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReplayToolCallsOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Calls re-executed
+    pub replayed: usize,
+    /// Replayed calls whose output matched the recording
+    pub matched: usize,
+    /// Replayed calls whose output differed from the recording
+    pub drifted: usize,
+    /// Calls refused because the tool is not read-only
+    pub refused: usize,
+    /// Calls that could not be executed
+    pub errors: usize,
+    /// Per-call results in replay order
+    pub results: Vec<ReplayResult>,
+}
+
+/// Outcome of replaying one call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayStatus {
+    /// Output equals the recorded output
+    Matched,
+    /// Output differs from the recorded output
+    Drifted,
+    /// Executed, but there was no recorded output to compare against
+    Unverified,
+    /// Not executed: the tool is not declared read-only or is destructive
+    Refused,
+    /// Not executed: the server or tool could not be reached
+    Error,
+}
+
+/// Result of replaying one call
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReplayResult {
+    /// Position in the replayed step list
+    pub index: usize,
+    /// Tool that was replayed
+    pub tool_name: String,
+    /// Backend server the call was sent to
+    pub server: String,
+    /// Outcome
+    pub status: ReplayStatus,
+    /// Why the call was refused or failed, or notes about the comparison
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Duration of the replayed call in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Differences between recorded and replayed output
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub differences: Vec<OutputDifference>,
+}
+
+/// A value that differs between the recorded and replayed output
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OutputDifference {
+    /// JSON Pointer to the differing value
+    pub path: String,
+    /// Recorded value (None if absent)
+    pub recorded: Option<Value>,
+    /// Replayed value (None if absent)
+    pub replayed: Option<Value>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for ReplayToolCallsArgs {
+    type Output = ReplayToolCallsOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = REPLAY_TOOL_CALLS;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Re-execute recorded read-only tool calls and diff their outputs against the recording to detect environment drift; destructive tools are refused";
+}
+
+register_tool_metadata!(ReplayToolCallsArgs);
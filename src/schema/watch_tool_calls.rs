@@ -0,0 +1,92 @@
+//! Schema types for watch_tool_calls tool
+
+use kodegen_config::CATEGORY_INTROSPECTION;
+use kodegen_mcp_schema::ToolArgs;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::inspect_tool_calls::ConnectionCall;
+use super::register_tool_metadata;
+use crate::render::SummaryStyle;
+
+/// Tool name for `watch_tool_calls`
+pub const WATCH_TOOL_CALLS: &str = "watch_tool_calls";
+
+// ============================================================================
+// TOOL ARGUMENTS
+// ============================================================================
+
+/// Default value for max_calls
+fn default_max_calls() -> usize {
+    50
+}
+
+/// Default value for timeout_secs
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+/// Arguments for `watch_tool_calls` tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct WatchToolCallsArgs {
+    /// Only stream calls of this tool
+    #[serde(default)]
+    pub tool_name: Option<String>,
+
+    /// Stream calls recorded at or after this ISO 8601 timestamp first, then new ones (default: only new calls)
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Watch another connection instead of the calling one (subject to the access policy)
+    #[serde(default)]
+    pub connection_id: Option<String>,
+
+    /// Watch every known connection (subject to the access policy)
+    #[serde(default)]
+    pub all_connections: bool,
+
+    /// Stop after this many calls (default: 50)
+    #[serde(default = "default_max_calls")]
+    pub max_calls: usize,
+
+    /// Stop after this many seconds (default: 60, max: 600)
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Summary style: "ansi", "plain" or "markdown" (default: KODEGEN_INTROSPECTION_STYLE, plain if NO_COLOR is set, else ansi)
+    #[serde(default)]
+    pub style: Option<SummaryStyle>,
+}
+
+// ============================================================================
+// OUTPUT TYPES
+// ============================================================================
+
+/// Output from `watch_tool_calls` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WatchToolCallsOutput {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Number of calls streamed
+    pub count: usize,
+    /// Why the watch ended: "max_calls", "timeout" or "cancelled"
+    pub stopped_by: String,
+    /// Streamed calls, oldest first, tagged with their connection (arguments and outputs redacted)
+    pub calls: Vec<ConnectionCall>,
+}
+
+// ============================================================================
+// TOOL ARGS TRAIT IMPLEMENTATION (Args→Output Binding)
+// ============================================================================
+
+impl ToolArgs for WatchToolCallsArgs {
+    type Output = WatchToolCallsOutput;
+    type Prompts = InspectToolCallsPrompts;
+
+    const NAME: &'static str = WATCH_TOOL_CALLS;
+    const CATEGORY: &'static kodegen_config::Category = CATEGORY_INTROSPECTION;
+    const DESCRIPTION: &'static str = "Stream tool calls as they complete via progress notifications, with the same filters as inspect_tool_calls";
+}
+
+register_tool_metadata!(WatchToolCallsArgs);
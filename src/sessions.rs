@@ -0,0 +1,110 @@
+//! Session model shared by the usage tracker and history-based tools
+//!
+//! A session is a run of tool calls with no gap longer than the session
+//! timeout between consecutive calls.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::history::RecordedCall;
+
+/// Default inactivity gap after which a new session starts
+pub const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// A single session of tool activity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SessionRecord {
+    /// Session id derived from the start time (e.g. "20241012T200000Z")
+    pub id: String,
+    /// First call in the session (Unix timestamp)
+    pub start: i64,
+    /// Last call in the session (Unix timestamp)
+    pub end: i64,
+    /// Number of tool calls in the session
+    pub call_count: u64,
+    /// Number of failed tool calls in the session
+    pub failed_calls: u64,
+    /// Distinct tools called during the session
+    pub tools_used: BTreeSet<String>,
+}
+
+impl SessionRecord {
+    /// Open an empty session starting at `start` (Unix timestamp)
+    #[must_use]
+    pub fn open(start: i64) -> Self {
+        Self {
+            id: session_id(start),
+            start,
+            end: start,
+            call_count: 0,
+            failed_calls: 0,
+            tools_used: BTreeSet::new(),
+        }
+    }
+
+    /// Record a call made at `at` (Unix timestamp)
+    pub fn record(&mut self, tool_name: &str, failed: bool, at: i64) {
+        self.call_count += 1;
+        if failed {
+            self.failed_calls += 1;
+        }
+        self.tools_used.insert(tool_name.to_string());
+        self.end = self.end.max(at);
+    }
+
+    /// Session length in seconds
+    #[must_use]
+    pub fn duration_secs(&self) -> i64 {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// Stable session id for a session starting at `start` (Unix timestamp)
+#[must_use]
+pub fn session_id(start: i64) -> String {
+    DateTime::<Utc>::from_timestamp(start, 0)
+        .map_or_else(|| start.to_string(), |dt| dt.format("%Y%m%dT%H%M%SZ").to_string())
+}
+
+/// Whether a call at `now` starts a new session after activity at `last_used`
+#[must_use]
+pub fn is_new_session(last_used: i64, now: i64, timeout: Duration) -> bool {
+    (now - last_used) > i64::try_from(timeout.as_secs()).unwrap_or(i64::MAX)
+}
+
+/// Split chronological history into sessions
+///
+/// Returns each session with the index range of its calls in `calls`.
+/// Calls with malformed timestamps stay in the session they appear in.
+#[must_use]
+pub fn segment(calls: &[RecordedCall], timeout: Duration) -> Vec<(SessionRecord, Range<usize>)> {
+    let mut sessions: Vec<(SessionRecord, Range<usize>)> = Vec::new();
+    let mut last_time: Option<i64> = None;
+
+    for (idx, call) in calls.iter().enumerate() {
+        let at = call.time.map(|t| t.timestamp()).or(last_time).unwrap_or(0);
+
+        let starts_new = match (sessions.last(), last_time) {
+            (None, _) => true,
+            (Some(_), Some(last)) => is_new_session(last, at, timeout),
+            (Some(_), None) => false,
+        };
+
+        if starts_new {
+            sessions.push((SessionRecord::open(at), idx..idx));
+        }
+
+        if let Some((session, range)) = sessions.last_mut() {
+            session.record(&call.record.tool_name, call.is_failure(), at);
+            range.end = idx + 1;
+        }
+
+        last_time = Some(at);
+    }
+
+    sessions
+}
@@ -0,0 +1,413 @@
+//! Live streaming of completed tool calls
+//!
+//! kodegend only answers history queries, so a [`CallWatcher`] polls a set
+//! of connections and hands out each call once, as soon as it shows up in
+//! the history. The watcher backs both the `watch_tool_calls` MCP tool
+//! (progress notifications) and the Server-Sent Events endpoint served here:
+//!
+//! ```text
+//! GET /tool-calls?connection_id=<id>[&tool_name=<name>][&since=<rfc3339>]
+//! GET /tool-calls?all_connections=true
+//! ```
+//!
+//! Like `/metrics`, the endpoint runs on its own listener because
+//! `kodegen_server_http` owns the MCP router. HTTP clients have no MCP
+//! connection of their own, so every request reads other connections' data
+//! and is subject to the access policy; the endpoint only binds to loopback
+//! addresses.
+
+use axum::Router;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::get;
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use kodegen_mcp_schema::McpError;
+use kodegen_server_http::ShutdownHook;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::access::AccessPolicy;
+use crate::export;
+use crate::history::{fetch_history, list_connections, parse_timestamp, RecordedCall};
+use crate::redact::Redactor;
+
+/// Environment variable holding the address to serve `/tool-calls` on (unset: disabled)
+pub const STREAM_ADDR_ENV: &str = "KODEGEN_INTROSPECTION_STREAM_ADDR";
+
+/// How often watchers query kodegend for new calls
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Longest a single MCP watch or wait may block
+pub const MAX_WAIT_SECS: u64 = 600;
+
+// ============================================================================
+// CALL WATCHER
+// ============================================================================
+
+/// Filters shared with `inspect_tool_calls`
+#[derive(Debug, Clone, Default)]
+pub struct CallFilter {
+    /// Only calls of this tool
+    pub tool_name: Option<String>,
+    /// Replay calls recorded at or after this time before streaming new ones
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Identity of a call within one history timestamp: connection, server, tool,
+/// arguments, output and how many identical calls precede it at that timestamp
+type CallKey = (Option<String>, String, String, String, String, usize);
+
+/// Hands out each call of the watched connections exactly once
+pub struct CallWatcher {
+    connections: Vec<String>,
+    filter: CallFilter,
+    /// Timestamp of the newest call handed out (None before the first poll)
+    watermark: Option<String>,
+    /// Calls at the watermark timestamp already handed out
+    seen: HashSet<CallKey>,
+    /// Merged history of the watched connections as of the last poll
+    history: Vec<RecordedCall>,
+}
+
+impl CallWatcher {
+    #[must_use]
+    pub fn new(connections: Vec<String>, filter: CallFilter) -> Self {
+        Self {
+            connections,
+            filter,
+            watermark: None,
+            seen: HashSet::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Merged history of the watched connections as of the last poll, oldest first
+    #[must_use]
+    pub fn history(&self) -> &[RecordedCall] {
+        &self.history
+    }
+
+    /// Calls recorded since the previous poll that match the filter, oldest first
+    ///
+    /// Without `since`, the first poll only records where history currently
+    /// ends, so only calls completing afterwards are returned. Connections
+    /// whose history cannot be fetched are skipped unless none answer.
+    pub fn poll(&mut self) -> Result<Vec<RecordedCall>, McpError> {
+        let mut calls = Vec::new();
+        let mut last_error = None;
+        let mut answered = false;
+        for connection_id in &self.connections {
+            match fetch_history(connection_id) {
+                Ok(history) => {
+                    answered = true;
+                    calls.extend(history.calls);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        if let (false, Some(e)) = (answered, last_error) {
+            return Err(e);
+        }
+        Ok(self.advance(calls))
+    }
+
+    /// Hand out the calls of a freshly fetched history not handed out before
+    fn advance(&mut self, mut calls: Vec<RecordedCall>) -> Vec<RecordedCall> {
+        calls.sort_by(|a, b| a.record.timestamp.cmp(&b.record.timestamp));
+
+        let first_poll = self.watermark.is_none();
+        let at_watermark: HashMap<usize, CallKey> = self
+            .watermark
+            .as_deref()
+            .map(|watermark| keys_at(&calls, watermark))
+            .unwrap_or_default();
+        let fresh: Vec<RecordedCall> = calls
+            .iter()
+            .enumerate()
+            .filter(|(index, call)| match self.watermark.as_deref() {
+                Some(watermark) => {
+                    call.record.timestamp.as_str() > watermark
+                        || at_watermark.get(index).is_some_and(|key| !self.seen.contains(key))
+                }
+                None => true,
+            })
+            .map(|(_, call)| call.clone())
+            .collect();
+
+        // Advance the watermark over everything seen, matching or not
+        if let Some(newest) = fresh.last().map(|call| call.record.timestamp.clone()) {
+            if self.watermark.as_deref() != Some(newest.as_str()) {
+                self.seen.clear();
+            }
+            self.seen.extend(keys_at(&calls, &newest).into_values());
+            self.watermark = Some(newest);
+        } else if first_poll {
+            self.watermark = Some(String::new());
+        }
+
+        self.history = calls;
+
+        if first_poll && self.filter.since.is_none() {
+            return Vec::new();
+        }
+
+        fresh
+            .into_iter()
+            .filter(|call| self.filter.tool_name.as_ref().is_none_or(|name| &call.record.tool_name == name))
+            .filter(|call| match (self.filter.since, call.time) {
+                (Some(since), Some(time)) => time >= since,
+                _ => true,
+            })
+            .collect()
+    }
+
+    /// [`Self::poll`] on the blocking pool (kodegend IPC is synchronous)
+    ///
+    /// Returns None if the blocking task could not complete (runtime shutdown).
+    pub async fn poll_blocking(self) -> Option<(Self, Result<Vec<RecordedCall>, McpError>)> {
+        tokio::task::spawn_blocking(move || {
+            let mut watcher = self;
+            let calls = watcher.poll();
+            (watcher, calls)
+        })
+        .await
+        .ok()
+    }
+}
+
+/// Keys of the calls recorded at `timestamp`, by index in `calls`
+///
+/// Identical calls are told apart by their order, which histories keep.
+fn keys_at(calls: &[RecordedCall], timestamp: &str) -> HashMap<usize, CallKey> {
+    let mut ordinals: HashMap<(Option<String>, String, String, String, String), usize> = HashMap::new();
+    calls
+        .iter()
+        .enumerate()
+        .filter(|(_, call)| call.record.timestamp == timestamp)
+        .map(|(index, call)| {
+            let identity = (
+                call.connection_id.clone(),
+                call.server.clone(),
+                call.record.tool_name.clone(),
+                call.record.args_json.clone(),
+                call.record.output_json.clone(),
+            );
+            let ordinal = ordinals.entry(identity.clone()).or_insert(0);
+            let (connection_id, server, tool_name, args_json, output_json) = identity;
+            let key = (connection_id, server, tool_name, args_json, output_json, *ordinal);
+            *ordinal += 1;
+            (index, key)
+        })
+        .collect()
+}
+
+// ============================================================================
+// SSE ENDPOINT
+// ============================================================================
+
+/// Handle to a running streaming endpoint
+pub struct StreamHandle {
+    local_addr: SocketAddr,
+    shutdown: watch::Sender<bool>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl StreamHandle {
+    /// Address the endpoint is listening on
+    #[must_use]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// End open streams and wait for the server to stop
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(true);
+        let _ = self.task.await;
+    }
+
+    /// Hook that stops the endpoint when the MCP server shuts down its managers
+    #[must_use]
+    pub fn into_shutdown_hook(self) -> StreamShutdown {
+        StreamShutdown(tokio::sync::Mutex::new(Some(self)))
+    }
+}
+
+/// [`ShutdownHook`] owning a running streaming endpoint
+pub struct StreamShutdown(tokio::sync::Mutex<Option<StreamHandle>>);
+
+impl ShutdownHook for StreamShutdown {
+    fn shutdown(&self) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + '_>> {
+        Box::pin(async move {
+            if let Some(handle) = self.0.lock().await.take() {
+                handle.shutdown().await;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Serve `/tool-calls` on a pre-bound listener (non-blocking)
+pub async fn start_stream_server(listener: tokio::net::TcpListener) -> anyhow::Result<StreamHandle> {
+    let local_addr = listener.local_addr()
+        .map_err(|e| anyhow::anyhow!("Failed to get stream listener address: {}", e))?;
+
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    let router = Router::new()
+        .route("/tool-calls", get(tool_calls_handler))
+        .with_state(shutdown_rx.clone());
+
+    let task = tokio::spawn(async move {
+        let mut shutdown_rx = shutdown_rx;
+        let server = axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                let _ = shutdown_rx.changed().await;
+            });
+        if let Err(e) = server.await {
+            log::error!("Tool call stream endpoint failed: {e}");
+        }
+    });
+
+    log::info!("Tool call stream available on http://{local_addr}/tool-calls");
+
+    Ok(StreamHandle { local_addr, shutdown, task })
+}
+
+/// Start the streaming endpoint if `KODEGEN_INTROSPECTION_STREAM_ADDR` is set
+pub async fn start_from_env() -> anyhow::Result<Option<StreamHandle>> {
+    let Ok(addr) = std::env::var(STREAM_ADDR_ENV) else {
+        return Ok(None);
+    };
+
+    let addr: SocketAddr = addr.parse()
+        .map_err(|e| anyhow::anyhow!("Invalid {STREAM_ADDR_ENV} '{addr}': {e}"))?;
+    if !addr.ip().is_loopback() {
+        anyhow::bail!("{STREAM_ADDR_ENV} must be a loopback address, got {addr}");
+    }
+    let listener = tokio::net::TcpListener::bind(addr).await
+        .map_err(|e| anyhow::anyhow!("Failed to bind stream endpoint to {}: {}", addr, e))?;
+
+    start_stream_server(listener).await.map(Some)
+}
+
+/// Query parameters of `/tool-calls`
+#[derive(Debug, Deserialize)]
+struct StreamParams {
+    connection_id: Option<String>,
+    #[serde(default)]
+    all_connections: bool,
+    tool_name: Option<String>,
+    since: Option<String>,
+}
+
+async fn tool_calls_handler(
+    State(shutdown): State<watch::Receiver<bool>>,
+    Query(params): Query<StreamParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    let since = match params.since.as_deref() {
+        Some(since) => Some(parse_timestamp(since)
+            .ok_or_else(|| (StatusCode::BAD_REQUEST, format!("Invalid since timestamp: {since}")))?),
+        None => None,
+    };
+
+    // Without a requester identity, even a single connection counts as cross-connection
+    let connections = match (params.connection_id, params.all_connections) {
+        (Some(_), true) => {
+            return Err((StatusCode::BAD_REQUEST, "connection_id and all_connections are mutually exclusive".to_string()));
+        }
+        (Some(connection_id), false) => {
+            let policy = AccessPolicy::load().map_err(error_response)?;
+            policy.authorize_other_connection(&connection_id).map_err(error_response)?;
+            vec![connection_id]
+        }
+        (None, true) => {
+            let policy = AccessPolicy::load().map_err(error_response)?;
+            list_connections(policy).await.map_err(error_response)?
+        }
+        (None, false) => {
+            return Err((StatusCode::BAD_REQUEST, "connection_id or all_connections=true is required".to_string()));
+        }
+    };
+    let redactor = Redactor::load().map_err(error_response)?;
+    let watcher = CallWatcher::new(connections, CallFilter { tool_name: params.tool_name, since });
+
+    let state = (watcher, redactor, VecDeque::<RecordedCall>::new(), shutdown);
+    let events = futures::stream::unfold(state, |(mut watcher, redactor, mut pending, mut shutdown)| async move {
+        loop {
+            if let Some(mut call) = pending.pop_front() {
+                redactor.redact_record(&mut call.record);
+                let event = Event::default()
+                    .event("tool_call")
+                    .data(export::call_json(&call, true).to_string());
+                return Some((Ok(event), (watcher, redactor, pending, shutdown)));
+            }
+
+            let (next, calls) = watcher.poll_blocking().await?;
+            watcher = next;
+            match calls {
+                Ok(calls) => pending.extend(calls),
+                Err(e) => log::debug!("Tool call stream poll failed: {e}"),
+            }
+            if pending.is_empty() {
+                tokio::select! {
+                    () = tokio::time::sleep(POLL_INTERVAL) => {}
+                    _ = shutdown.changed() => return None,
+                }
+            }
+        }
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+fn error_response(e: McpError) -> (StatusCode, String) {
+    let status = match e {
+        McpError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kodegen_mcp_schema::introspection::ToolCallRecord;
+
+    fn call(tool_name: &str, timestamp: &str) -> RecordedCall {
+        RecordedCall {
+            server: "filesystem".to_string(),
+            time: parse_timestamp(timestamp),
+            connection_id: Some("conn-1".to_string()),
+            record: ToolCallRecord {
+                tool_name: tool_name.to_string(),
+                timestamp: timestamp.to_string(),
+                duration_ms: Some(1),
+                args_json: r#"{"path":"src/lib.rs"}"#.to_string(),
+                output_json: "{}".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn identical_calls_at_one_timestamp_are_each_handed_out() {
+        let mut watcher = CallWatcher::new(vec!["conn-1".to_string()], CallFilter::default());
+        let mut history = vec![call("fs_read_file", "2024-10-12T10:00:00Z")];
+        assert!(watcher.advance(history.clone()).is_empty());
+
+        // A second, identical call completes within the same second
+        history.push(call("fs_read_file", "2024-10-12T10:00:01Z"));
+        history.push(call("fs_read_file", "2024-10-12T10:00:01Z"));
+        assert_eq!(watcher.advance(history.clone()).len(), 2);
+        assert!(watcher.advance(history.clone()).is_empty());
+
+        history.push(call("fs_read_file", "2024-10-12T10:00:01Z"));
+        assert_eq!(watcher.advance(history).len(), 1);
+    }
+}
@@ -3,6 +3,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+
+use crate::render::SummaryStyle;
+use crate::sessions::{self, SessionRecord, DEFAULT_SESSION_TIMEOUT};
 
 /// Update event for background processor
 enum StatsUpdate {
@@ -10,8 +14,8 @@ enum StatsUpdate {
     Failure(String), // tool_name
 }
 
-// Session timeout: 30 minutes of inactivity = new session
-const SESSION_TIMEOUT_SECS: i64 = 30 * 60;
+// Number of most recent session records kept in the stats file
+const MAX_SESSIONS: usize = 100;
 
 /// Statistics tracked for tool usage
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +40,10 @@ pub struct UsageStats {
     pub first_used: i64, // Unix timestamp
     pub last_used: i64,  // Unix timestamp
     pub total_sessions: u64,
+
+    // Most recent sessions, oldest first
+    #[serde(default)]
+    pub sessions: Vec<SessionRecord>,
 }
 
 impl Default for UsageStats {
@@ -55,10 +63,99 @@ impl Default for UsageStats {
             first_used: now,
             last_used: now,
             total_sessions: 1,
+            sessions: Vec::new(),
         }
     }
 }
 
+impl UsageStats {
+    /// Statistics with no recorded activity (zero sessions)
+    fn empty() -> Self {
+        Self {
+            total_sessions: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Fold another set of statistics into this one
+    ///
+    /// Counters are summed, the time range is widened to cover both.
+    pub fn merge(&mut self, other: &Self) {
+        self.filesystem_operations += other.filesystem_operations;
+        self.terminal_operations += other.terminal_operations;
+        self.edit_operations += other.edit_operations;
+        self.search_operations += other.search_operations;
+        self.config_operations += other.config_operations;
+        self.process_operations += other.process_operations;
+
+        self.total_tool_calls += other.total_tool_calls;
+        self.successful_calls += other.successful_calls;
+        self.failed_calls += other.failed_calls;
+
+        for (tool_name, count) in &other.tool_counts {
+            *self.tool_counts.entry(tool_name.clone()).or_insert(0) += count;
+        }
+
+        self.first_used = self.first_used.min(other.first_used);
+        self.last_used = self.last_used.max(other.last_used);
+        self.total_sessions += other.total_sessions;
+
+        self.sessions.extend(other.sessions.iter().cloned());
+        self.sessions.sort_by_key(|session| session.start);
+        self.trim_sessions();
+    }
+
+    /// Record a call in the current session, opening a new one after `timeout` of inactivity
+    fn record_session_call(&mut self, tool_name: &str, failed: bool, now: i64, timeout: Duration) {
+        let new_session = sessions::is_new_session(self.last_used, now, timeout);
+        if new_session {
+            self.total_sessions += 1;
+        }
+
+        // Stats files written before session records existed have no open session
+        if new_session || self.sessions.is_empty() {
+            self.sessions.push(SessionRecord::open(now));
+            self.trim_sessions();
+        }
+
+        if let Some(session) = self.sessions.last_mut() {
+            session.record(tool_name, failed, now);
+        }
+    }
+
+    fn trim_sessions(&mut self) {
+        let excess = self.sessions.len().saturating_sub(MAX_SESSIONS);
+        self.sessions.drain(..excess);
+    }
+}
+
+/// Statistics loaded from a single `stats_{instance_id}.json` file
+#[derive(Debug, Clone)]
+pub struct InstanceStats {
+    /// Instance id taken from the file name
+    pub instance_id: String,
+    /// Statistics merged across every connection in the file
+    pub stats: UsageStats,
+    /// Per-connection statistics (empty for files written by a single-tracker instance)
+    pub connections: HashMap<String, UsageStats>,
+}
+
+/// On-disk layout of a stats file
+///
+/// Standalone trackers persist a single `UsageStats`, while the HTTP server
+/// tracker persists one entry per connection.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StatsFile {
+    Single(UsageStats),
+    PerConnection(HashMap<String, UsageStats>),
+}
+
+/// Instance id of a `stats_{instance_id}.json` file name
+pub(crate) fn stats_instance_id(file_name: &str) -> Option<&str> {
+    file_name.strip_prefix("stats_")?.strip_suffix(".json")
+}
+
 /// Usage tracker that manages statistics for all tool calls
 #[derive(Clone)]
 pub struct UsageTracker {
@@ -93,10 +190,98 @@ impl UsageTracker {
     }
 
     /// Get stats file path using kodegen_config (directory creation happens async)
-    fn get_stats_file_path(instance_id: &str) -> PathBuf {
-        kodegen_config::KodegenConfig::data_dir()
-            .map(|dir| dir.join("stats").join(format!("stats_{instance_id}.json")))
-            .unwrap_or_else(|_| PathBuf::from(format!("stats_{instance_id}.json")))
+    pub(crate) fn get_stats_file_path(instance_id: &str) -> PathBuf {
+        let file_name = format!("stats_{instance_id}.json");
+        Self::stats_dir().map_or_else(|| PathBuf::from(&file_name), |dir| dir.join(&file_name))
+    }
+
+    /// Directory holding every instance's stats file
+    ///
+    /// None (with a warning) when kodegen has no data directory: readers then
+    /// find no stats files instead of scanning the current directory.
+    pub(crate) fn stats_dir() -> Option<PathBuf> {
+        match kodegen_config::KodegenConfig::data_dir() {
+            Ok(dir) => Some(dir.join("stats")),
+            Err(e) => {
+                log::warn!("No kodegen data directory, stats files are unavailable: {e}");
+                None
+            }
+        }
+    }
+
+    /// Load the stats file of a single instance
+    pub async fn load_instance(instance_id: &str) -> Option<InstanceStats> {
+        let path = Self::stats_dir()?.join(format!("stats_{instance_id}.json"));
+        let contents = tokio::fs::read_to_string(&path).await.ok()?;
+        Self::parse_instance(instance_id, &contents)
+    }
+
+    /// Load every `stats_*.json` file on disk, sorted by instance id
+    ///
+    /// Files that cannot be read or parsed are skipped.
+    pub async fn load_all_instances() -> Vec<InstanceStats> {
+        let mut instances = Vec::new();
+
+        let Some(stats_dir) = Self::stats_dir() else {
+            return instances;
+        };
+        let Ok(mut entries) = tokio::fs::read_dir(stats_dir).await else {
+            return instances;
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let file_name = entry.file_name();
+            let Some(instance_id) = file_name.to_str().and_then(stats_instance_id) else {
+                continue;
+            };
+
+            match tokio::fs::read_to_string(entry.path()).await {
+                Ok(contents) => {
+                    if let Some(instance) = Self::parse_instance(instance_id, &contents) {
+                        instances.push(instance);
+                    } else {
+                        log::warn!("Skipping unparseable stats file {}", entry.path().display());
+                    }
+                }
+                Err(e) => log::warn!("Failed to read stats file {}: {}", entry.path().display(), e),
+            }
+        }
+
+        instances.sort_by(|a, b| a.instance_id.cmp(&b.instance_id));
+        instances
+    }
+
+    /// Merge a set of instances into one lifetime total
+    #[must_use]
+    pub fn aggregate_instances(instances: &[InstanceStats]) -> UsageStats {
+        Self::merge_all(instances.iter().map(|instance| &instance.stats))
+    }
+
+    fn merge_all<'a>(mut stats: impl Iterator<Item = &'a UsageStats>) -> UsageStats {
+        let Some(first) = stats.next() else {
+            return UsageStats::empty();
+        };
+
+        let mut total = first.clone();
+        for other in stats {
+            total.merge(other);
+        }
+        total
+    }
+
+    pub(crate) fn parse_instance(instance_id: &str, contents: &str) -> Option<InstanceStats> {
+        let (stats, connections) = match serde_json::from_str::<StatsFile>(contents).ok()? {
+            StatsFile::Single(stats) => (stats, HashMap::new()),
+            StatsFile::PerConnection(connections) => {
+                (Self::merge_all(connections.values()), connections)
+            }
+        };
+
+        Some(InstanceStats {
+            instance_id: instance_id.to_string(),
+            stats,
+            connections,
+        })
     }
 
     /// Load stats from disk or create default (async)
@@ -107,17 +292,9 @@ impl UsageTracker {
         }
     }
 
-    /// Check if this is a new session (30+ min since last activity)
-    fn is_new_session(last_used: i64) -> bool {
-        let now = chrono::Utc::now().timestamp();
-        (now - last_used) > SESSION_TIMEOUT_SECS
-    }
-
     /// Get tool category for categorization using inventory system
     fn get_category(tool_name: &str) -> Option<&'static str> {
-        inventory::iter::<kodegen_mcp_schema::ToolMetadata>()
-            .find(|tool| tool.name == tool_name)
-            .map(|tool| tool.category.name)
+        crate::activity::tool_category(tool_name)
     }
 
     /// Track a successful tool call (fire-and-forget, never blocks)
@@ -165,27 +342,25 @@ impl UsageTracker {
                             let mut stats_guard = stats.write();
                             let now = chrono::Utc::now().timestamp();
 
-                            // Check if new session (30 min timeout)
-                            if Self::is_new_session(stats_guard.last_used) {
-                                stats_guard.total_sessions += 1;
-                            }
-
-                            // Update common counters
-                            stats_guard.total_tool_calls += 1;
-                            stats_guard.last_used = now;
-
                             // Process update type
-                            let tool_name = match update {
+                            let (tool_name, failed) = match update {
                                 StatsUpdate::Success(name) => {
                                     stats_guard.successful_calls += 1;
-                                    name
+                                    (name, false)
                                 }
                                 StatsUpdate::Failure(name) => {
                                     stats_guard.failed_calls += 1;
-                                    name
+                                    (name, true)
                                 }
                             };
 
+                            // Attribute the call to a session (opens a new one after the timeout)
+                            stats_guard.record_session_call(&tool_name, failed, now, DEFAULT_SESSION_TIMEOUT);
+
+                            // Update common counters
+                            stats_guard.total_tool_calls += 1;
+                            stats_guard.last_used = now;
+
                             // Update tool-specific counter
                             *stats_guard.tool_counts.entry(tool_name.clone()).or_insert(0) += 1;
 
@@ -327,9 +502,15 @@ impl UsageTracker {
         )
     }
 
-    /// Get formatted summary with ANSI colors and Nerd Font icons (2-line output)
+    /// Get formatted 2-line summary in the process default style (see [`SummaryStyle::from_env`])
     #[must_use]
     pub fn get_formatted_summary(&self) -> String {
+        self.get_styled_summary(SummaryStyle::from_env())
+    }
+
+    /// Get formatted 2-line summary in the given style
+    #[must_use]
+    pub fn get_styled_summary(&self, style: SummaryStyle) -> String {
         let stats = self.stats.read();
 
         // Calculate counts needed for display
@@ -337,13 +518,14 @@ impl UsageTracker {
         let unique_tools = stats.tool_counts.len();
         let error_count = stats.failed_calls;
 
-        // Format with magenta color only on line 1, using Nerd Font icons
-        format!(
-            "\x1b[35mUsage Statistics\x1b[0m\n\
-             Total calls: {} · Unique tools: {} · Errors: {}",
-            total_calls,
-            unique_tools,
-            error_count
+        style.summary(
+            "Usage Statistics",
+            &format!(
+                "Total calls: {} · Unique tools: {} · Errors: {}",
+                total_calls,
+                unique_tools,
+                error_count
+            ),
         )
     }
 
@@ -353,6 +535,18 @@ impl UsageTracker {
         self.stats.read().clone()
     }
 
+    /// Get the most recent session records, oldest first
+    #[must_use]
+    pub fn get_sessions(&self) -> Vec<SessionRecord> {
+        self.stats.read().sessions.clone()
+    }
+
+    /// Get the session currently being recorded (None before the first call)
+    #[must_use]
+    pub fn current_session(&self) -> Option<SessionRecord> {
+        self.stats.read().sessions.last().cloned()
+    }
+
     fn format_timestamp(timestamp: i64) -> String {
         chrono::DateTime::from_timestamp(timestamp, 0).map_or_else(
             || "Unknown".to_string(),
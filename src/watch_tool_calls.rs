@@ -0,0 +1,144 @@
+use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolArgs, ToolResponse};
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::introspection::InspectToolCallsPrompts;
+use std::time::Duration;
+
+use crate::export;
+use crate::export_session::parse_bound;
+use crate::history::{resolve_connections, RecordedCall};
+use crate::redact::Redactor;
+use crate::render::SummaryStyle;
+use crate::schema::inspect_tool_calls::ConnectionCall;
+use crate::schema::watch_tool_calls::{WatchToolCallsArgs, WatchToolCallsOutput, WATCH_TOOL_CALLS};
+use crate::stream::{CallFilter, CallWatcher, MAX_WAIT_SECS, POLL_INTERVAL};
+
+// ============================================================================
+// TOOL STRUCT
+// ============================================================================
+
+#[derive(Clone, Default)]
+pub struct WatchToolCallsTool;
+
+impl WatchToolCallsTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// ============================================================================
+// TOOL IMPLEMENTATION
+// ============================================================================
+
+impl Tool for WatchToolCallsTool {
+    type Args = WatchToolCallsArgs;
+    type Prompts = InspectToolCallsPrompts;
+
+    fn name() -> &'static str {
+        WATCH_TOOL_CALLS
+    }
+
+    fn description() -> &'static str {
+        "Stream tool calls as they complete instead of polling inspect_tool_calls. \
+         Each new call is pushed as an MCP progress notification whose message \
+         is the call as a JSON object; the result lists every streamed call.\n\n\
+         Filter with tool_name and since (replays calls since that time first), \
+         watch a sibling with connection_id or everything with all_connections \
+         (subject to the access policy). The watch ends after max_calls calls, \
+         timeout_secs seconds or when cancelled. Arguments and outputs are \
+         redacted like inspect_tool_calls.\n\n\
+         Useful for:\n\
+         - Supervising sibling agents live\n\
+         - Waiting for a long-running agent's next steps\n\
+         - Tailing failures as they happen"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn idempotent() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as ToolArgs>::Output>, McpError> {
+        if args.max_calls == 0 {
+            return Err(McpError::invalid_arguments("max_calls must be greater than 0"));
+        }
+        if args.timeout_secs == 0 || args.timeout_secs > MAX_WAIT_SECS {
+            return Err(McpError::invalid_arguments(format!("timeout_secs must be between 1 and {MAX_WAIT_SECS}")));
+        }
+        let since = parse_bound(args.since.as_deref(), "since")?;
+
+        // Other connections are subject to the access policy
+        let connections = resolve_connections(&ctx, args.connection_id, args.all_connections).await?;
+
+        let redactor = Redactor::load()?;
+        let mut watcher = CallWatcher::new(connections, CallFilter { tool_name: args.tool_name, since });
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(args.timeout_secs);
+        let mut streamed: Vec<RecordedCall> = Vec::new();
+
+        let stopped_by = 'watch: loop {
+            let (next, calls) = watcher.poll_blocking().await
+                .ok_or_else(|| McpError::Other(anyhow::anyhow!("Tool call watcher stopped unexpectedly")))?;
+            watcher = next;
+
+            for mut call in calls? {
+                redactor.redact_record(&mut call.record);
+                // Delivery is best effort; the result still carries every call
+                ctx.notify(
+                    (streamed.len() + 1) as f64,
+                    Some(args.max_calls as f64),
+                    Some(export::call_json(&call, true).to_string()),
+                )
+                .await
+                .ok();
+                streamed.push(call);
+                if streamed.len() >= args.max_calls {
+                    break 'watch "max_calls";
+                }
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                break "timeout";
+            }
+            tokio::select! {
+                () = tokio::time::sleep_until(deadline.min(now + POLL_INTERVAL)) => {}
+                () = ctx.cancellation_token().cancelled() => break "cancelled",
+            }
+        };
+
+        // Summary in the requested style
+        let style = SummaryStyle::resolve(args.style);
+        let summary = style.summary(
+            "Tool Call Stream",
+            &format!(
+                "Streamed: {} · Stopped by: {} · Latest: {}",
+                streamed.len(),
+                stopped_by,
+                streamed.last().map_or("none", |call| call.record.tool_name.as_str())
+            ),
+        );
+
+        let output = WatchToolCallsOutput {
+            success: true,
+            count: streamed.len(),
+            stopped_by: stopped_by.to_string(),
+            calls: streamed
+                .into_iter()
+                .map(|call| ConnectionCall { connection_id: call.connection_id, record: call.record })
+                .collect(),
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}